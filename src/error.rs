@@ -0,0 +1,84 @@
+//! Errors returned by brickline
+//!
+//! Library consumers can match on the BricklineError variant to tell a
+//! missing file apart from a malformed wanted list, rather than digging
+//! through the message of an IOError.
+use crate::wanted::{ParseError, ValidationError};
+
+use std::fmt;
+use std::io::Error as IOError;
+
+use quick_xml::DeError;
+
+/// Where in a wanted list's XML a problem was found. Lines and columns both
+/// start at 1.
+#[derive(Debug, PartialEq)]
+pub struct XmlPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for XmlPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Everything that can go wrong loading, processing, or writing a wanted list
+#[derive(Debug)]
+pub enum BricklineError {
+    /// Reading or writing a file failed, or a command was given bad input
+    Io(IOError),
+    /// A wanted list isn't well-formed XML, or couldn't be serialized to XML.
+    /// The position of the problem is included when it could be found.
+    Xml(DeError, Option<XmlPosition>),
+    /// A wanted list field couldn't be parsed into its type
+    Parse(ParseError),
+    /// A wanted list has problems that would stop Bricklink accepting it
+    Validation(Vec<ValidationError>),
+}
+
+impl fmt::Display for BricklineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BricklineError::Io(e) => write!(f, "{}", e),
+            BricklineError::Xml(e, Some(position)) => {
+                write!(f, "Invalid wanted list XML at {}: {}", position, e)
+            }
+            BricklineError::Xml(e, None) => write!(f, "Invalid wanted list XML: {}", e),
+            BricklineError::Parse(e) => write!(f, "{}", e),
+            BricklineError::Validation(errors) => {
+                write!(f, "Found {} problem(s) in the wanted list", errors.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BricklineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BricklineError::Io(e) => Some(e),
+            BricklineError::Xml(e, _) => Some(e),
+            BricklineError::Parse(e) => Some(e),
+            BricklineError::Validation(_) => None,
+        }
+    }
+}
+
+impl From<IOError> for BricklineError {
+    fn from(e: IOError) -> BricklineError {
+        BricklineError::Io(e)
+    }
+}
+
+impl From<DeError> for BricklineError {
+    fn from(e: DeError) -> BricklineError {
+        BricklineError::Xml(e, None)
+    }
+}
+
+impl From<ParseError> for BricklineError {
+    fn from(e: ParseError) -> BricklineError {
+        BricklineError::Parse(e)
+    }
+}
@@ -1,281 +1,3399 @@
+pub mod colors;
+pub mod error;
+pub mod logging;
 pub mod wanted;
 
+use crate::error::{BricklineError, XmlPosition};
 use crate::wanted::{
-    gen_statistics, type_and_gen_statistics, Color, Item, ItemID, MinQty, SerdeWantedList,
-    WantedList, WantedListStatistics,
+    gen_statistics, gen_statistics_with, type_and_gen_statistics, Color, Condition, DefaultQty,
+    Indent, Item, ItemID, ItemType, MaxPrice, MinQty, OwnedItemColorHashKey, QtyFilled, Remarks,
+    SerdeWantedList, SortKey, WantedList, WantedListStatistics, DEFAULT_ID_SUFFIXES,
 };
 
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryFrom;
-use std::error;
 use std::fs::File;
-use std::io::{Error as IOError, ErrorKind, Read, Write};
-use std::path::PathBuf;
+use std::io::{BufWriter, Error as IOError, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
 
 use clap::ArgMatches;
 use quick_xml::de::from_str;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{DeError, Reader, Writer};
 
-/// The primary key of an WantedList Item
+/// The primary key of an WantedList Item. The condition is only part of
+/// the key when joining with `JoinKey::IdColorCondition`, and the ItemID is
+/// only owned when it has been normalized for a join.
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ItemColorHashKey<'a> {
-    item_id: &'a ItemID,
+    item_id: Cow<'a, ItemID>,
     color: &'a Option<Color>,
+    condition: Option<&'a Condition>,
 }
 
-/// Get user input from stdout
+impl ItemColorHashKey<'_> {
+    /// Do the two keys match on everything but Color?
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: Key to compare against
+    ///
+    fn matches_any_color(&self, other: &ItemColorHashKey<'_>) -> bool {
+        self.item_id == other.item_id && self.condition == other.condition
+    }
+}
+
+/// An owned copy of the join key of an Item, as used to report what a join
+/// changed. Like ItemColorHashKey, the Condition is only set when joining
+/// with `JoinKey::IdColorCondition`, the Color is None when joining with
+/// `JoinKey::IdOnly`, and the ItemID is normalized if the join normalizes
+/// IDs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OwnedJoinKey {
+    pub item_id: ItemID,
+    pub color: Option<Color>,
+    pub condition: Option<Condition>,
+}
+
+impl std::convert::From<&ItemColorHashKey<'_>> for OwnedJoinKey {
+    fn from(key: &ItemColorHashKey<'_>) -> OwnedJoinKey {
+        OwnedJoinKey {
+            item_id: key.item_id.clone().into_owned(),
+            color: key.color.clone(),
+            condition: key.condition.cloned(),
+        }
+    }
+}
+
+/// Prompt the user on stderr and read their response from stdin. Prompting
+/// on stderr keeps stdout clean for piping.
+///
+/// # Arguments
+///
+/// * `message`: What message do you want to prompt the user with?
+///
+fn prompt_input(message: &str) -> Result<String, std::io::Error> {
+    eprint!("{}", message);
+    std::io::stderr().flush()?;
+    let mut buf = String::new();
+    std::io::stdin().read_line(&mut buf)?;
+    Ok(buf)
+}
+
+/// Write a file. If the file already exists, prompt the user to ask
+/// if they want to overwrite it, unless `assume_yes` is set. Declining
+/// the overwrite returns an error rather than writing the file.
+///
+/// # Arguments
+///
+/// * `file_path`: Path to file to write
+/// * `content`: File content to write
+/// * `description`: What is being written, used in the status message
+/// * `assume_yes`: Overwrite an existing file without prompting
+///
+fn write_file_with_overwrite_prompt(
+    file_path: &PathBuf,
+    content: &String,
+    description: &str,
+    assume_yes: bool,
+) -> Result<(), std::io::Error> {
+    if file_path.exists() && !assume_yes {
+        let msg = format!(
+            "The file {} already exists. Do you want to overwrite this file? ",
+            file_path.display()
+        );
+        let overwrite = prompt_input(&msg)?;
+        let lower = overwrite.to_lowercase();
+        let trimmed = lower.trim();
+        if trimmed != "y" && trimmed != "yes" {
+            return Err(IOError::new(
+                ErrorKind::AlreadyExists,
+                "Exited without writing file",
+            ));
+        }
+    }
+
+    let mut writer = BufWriter::new(File::create(file_path)?);
+    println!("Writing {} to {}", description, file_path.display());
+    writer.write_all(content.as_bytes())?;
+    writer.flush()?;
+    verbose!("Wrote {} bytes to {}", content.len(), file_path.display());
+    Ok(())
+}
+
+/// Write a command's output file, honoring the global `--yes`, `--dry-run`,
+/// and `--crlf` flags. In a dry run nothing is written and there's no
+/// overwrite prompt, just a note of what would have been written.
+///
+/// # Arguments
+///
+/// * `file_path`: Path to file to write
+/// * `content`: File content to write
+/// * `description`: What is being written, used in the status message
+/// * `args`: Arguments to the command
+///
+fn write_output(
+    file_path: &PathBuf,
+    content: &String,
+    description: &str,
+    args: &ArgMatches,
+) -> Result<(), std::io::Error> {
+    if args.is_present("dry-run") {
+        println!(
+            "Dry run: not writing {} to {}",
+            description,
+            file_path.display()
+        );
+        return Ok(());
+    }
+    let content = if args.is_present("crlf") {
+        Cow::Owned(to_crlf(content))
+    } else {
+        Cow::Borrowed(content)
+    };
+    write_file_with_overwrite_prompt(file_path, &content, description, args.is_present("yes"))
+}
+
+/// Convert the line endings of a String to CRLF, as Bricklink's own exports
+/// and some Windows tools expect. Line endings that are already CRLF are
+/// left as they are, and a String without any newlines, like compact XML,
+/// is unchanged.
+///
+/// # Arguments
+///
+/// * `content`: String to convert
+///
+/// Example
+///
+/// ```
+/// use brickline::to_crlf;
+///
+/// assert_eq!(to_crlf("<INVENTORY>\n</INVENTORY>\r\n"), "<INVENTORY>\r\n</INVENTORY>\r\n");
+/// ```
+pub fn to_crlf(content: &str) -> String {
+    content.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+/// Read how a command should indent the wanted lists it writes from the
+/// global `--pretty` and `--indent` flags. Giving an indent implies
+/// `--pretty`; without either, wanted lists are written compact.
+///
+/// # Arguments
+///
+/// * `args`: Arguments to the command
+///
+fn xml_indent(args: &ArgMatches) -> Result<Option<Indent>, IOError> {
+    match args.value_of("indent") {
+        Some(indent) => Ok(Some(indent.parse::<Indent>()?)),
+        None if args.is_present("pretty") => Ok(Some(Indent::default())),
+        None => Ok(None),
+    }
+}
+
+/// Serialize a WantedList and write it to any sink, e.g. a file, a socket,
+/// or a `Vec<u8>`. Writes are buffered, and the writer is flushed before
+/// returning.
+///
+/// # Arguments
+///
+/// * `wanted_list`: WantedList to write
+/// * `indent`: How to indent the XML for reading and diffing, or None for a
+///   single line (see `WantedList::to_xml_indented`)
+/// * `writer`: Where to write the XML
+///
+/// # Example
+///
+/// ```
+/// use brickline::write_wanted_list;
+/// use brickline::wanted::{Indent, WantedList};
+///
+/// let mut buffer: Vec<u8> = Vec::new();
+/// write_wanted_list(WantedList { items: vec![] }, None, &mut buffer).unwrap();
+/// assert!(buffer.starts_with(b"<?xml"));
+///
+/// let mut tabbed: Vec<u8> = Vec::new();
+/// write_wanted_list(WantedList { items: vec![] }, Some(Indent::Tab), &mut tabbed).unwrap();
+/// assert!(tabbed.ends_with(b"\n"));
+/// ```
+pub fn write_wanted_list<W: Write>(
+    wanted_list: WantedList,
+    indent: Option<Indent>,
+    writer: W,
+) -> Result<(), BricklineError> {
+    let xml_string = wanted_list.to_xml_indented(indent)?;
+    let mut writer = BufWriter::new(writer);
+    writer.write_all(xml_string.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Given a path to an XML file, load that file to a String. A leading UTF-8
+/// byte order mark, as written by some Windows tools, is stripped.
+///
+/// # Arguments
+///
+/// * `file_path`: path to an XML file
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use brickline::xml_to_string;
+///
+/// let path = PathBuf::from("/home/user/path/to/file.xml");
+/// let xml_string = xml_to_string(&path);
+/// ```
+pub fn xml_to_string(file_path: &PathBuf) -> Result<String, IOError> {
+    let mut file = File::open(file_path)?;
+    let mut xml_string = String::new();
+    file.read_to_string(&mut xml_string)?;
+    match xml_string.strip_prefix('\u{feff}') {
+        Some(stripped) => Ok(stripped.to_string()),
+        None => Ok(xml_string),
+    }
+}
+
+/// Given a path to a file, read the file and deserialize it to an WantedList
+///
+/// # Arguments
+///
+/// * `file_path`: Path to file, as a `&str`, `Path`, or anything else that
+///   converts to a Path, so non-UTF-8 file names load too
+///
+/// Example
+///
+/// ```no_run
+/// use brickline::file_to_wanted_list;
+///
+/// let inventory = file_to_wanted_list("/path/to/wanted_list.xml");
+pub fn file_to_wanted_list<P: AsRef<Path>>(
+    file_path: P,
+) -> Result<(WantedList, WantedListStatistics), BricklineError> {
+    let resource_path = file_path.as_ref().to_path_buf();
+    verbose!("Loading {}", resource_path.display());
+    let resource_str = xml_to_string(&resource_path)?;
+    let serde_inventory = parse_wanted_list(&resource_str)?;
+    let (wanted_list, statistics) = type_and_gen_statistics(serde_inventory)?;
+    verbose!(
+        "Loaded {} items from {}",
+        wanted_list.len(),
+        resource_path.display()
+    );
+    Ok((wanted_list, statistics))
+}
+
+/// Fetch a wanted list from a URL, e.g. a Bricklink share link, and
+/// deserialize it. Any credentials need to be part of the URL. A failed
+/// request, including a non-2xx response, is returned as an IO error.
+/// Only available with the `network` feature.
+///
+/// # Arguments
+///
+/// * `url`: HTTP or HTTPS URL of the wanted list XML
+///
+/// Example
+///
+/// ```no_run
+/// use brickline::from_url;
+///
+/// let wanted_list = from_url("https://example.com/wanted_list.xml");
+/// ```
+#[cfg(feature = "network")]
+pub fn from_url(url: &str) -> Result<WantedList, BricklineError> {
+    let xml_string = ureq::get(url)
+        .call()
+        .map_err(|e| IOError::other(format!("Could not fetch {}: {}", url, e)))?
+        .into_string()?;
+    let xml_string = xml_string.strip_prefix('\u{feff}').unwrap_or(&xml_string);
+    parse_wanted_list_lenient(xml_string)
+}
+
+/// Deserialize a wanted list from an XML String. Along with Bricklink's own
+/// `<INVENTORY>` format, BrickStore's `<BrickStoreXML>` documents are
+/// accepted and mapped onto the same fields; any other root element is an
+/// error naming the root that was found. XML comments are skipped wherever
+/// they appear, even inside a field's value, so hand-edited lists parse;
+/// they aren't kept when the list is written back out. If the XML isn't
+/// well-formed, the returned `BricklineError::Xml` carries the line and
+/// column where the reader gave up, so the offending ITEM can be found in a
+/// large file.
+///
+/// # Arguments
+///
+/// * `xml_string`: Wanted list XML
+///
+/// Example
+///
+/// ```
+/// use brickline::parse_wanted_list;
+/// use brickline::error::BricklineError;
+///
+/// match parse_wanted_list("<INVENTORY>\n<ITEM>\n</INVENTORY>") {
+///     Err(BricklineError::Xml(_, Some(position))) => assert_eq!(position.line, 3),
+///     other => panic!("Expected a positioned XML error, got {:?}", other),
+/// }
+/// ```
+pub fn parse_wanted_list(xml_string: &str) -> Result<SerdeWantedList, BricklineError> {
+    let inventory_string = match root_element_name(xml_string).as_deref() {
+        // A document we can't find a root in is left to the deserializer,
+        // which reports where it went wrong
+        Some("INVENTORY") | None => Cow::Borrowed(xml_string),
+        Some("BrickStoreXML") => Cow::Owned(brickstore_to_inventory(xml_string)?),
+        Some(root) => {
+            return Err(BricklineError::Xml(
+                DeError::Custom(format!(
+                    "Unsupported root element <{}>, expected <INVENTORY> or <BrickStoreXML>",
+                    root
+                )),
+                None,
+            ))
+        }
+    };
+    from_str::<SerdeWantedList>(&inventory_string)
+        .map_err(|e| BricklineError::Xml(e, locate_xml_error(xml_string)))
+}
+
+/// Deserialize a wanted list from an XML String all the way to a typed
+/// WantedList. This is the entry point for untrusted input: it never panics,
+/// whatever it's given. Malformed XML, unknown roots, and field values that
+/// can't be converted, like an unknown ITEMTYPE or CONDITION code, are all
+/// returned as errors.
+///
+/// # Arguments
+///
+/// * `xml_string`: Wanted list XML
+///
+/// Example
+///
+/// ```
+/// use brickline::parse_wanted_list_lenient;
+/// use brickline::error::BricklineError;
+///
+/// let xml = "<INVENTORY><ITEM><ITEMTYPE>Z</ITEMTYPE><ITEMID>3001</ITEMID></ITEM></INVENTORY>";
+/// match parse_wanted_list_lenient(xml) {
+///     Err(BricklineError::Parse(e)) => assert_eq!(e.field, "ITEMTYPE"),
+///     other => panic!("Expected a parse error, got {:?}", other),
+/// }
+/// ```
+pub fn parse_wanted_list_lenient(xml_string: &str) -> Result<WantedList, BricklineError> {
+    let serde_wanted_list = parse_wanted_list(xml_string)?;
+    Ok(WantedList::try_from(serde_wanted_list)?)
+}
+
+/// The name of the first element in the XML, or None if the reader fails or
+/// finds no elements before then
+///
+/// # Arguments
+///
+/// * `xml_string`: Wanted list XML
+///
+fn root_element_name(xml_string: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml_string);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                return Some(String::from_utf8_lossy(e.name()).into_owned())
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            Ok(_) => buf.clear(),
+        }
+    }
+}
+
+/// BrickStore element names and the Bricklink element each maps to. Qty is
+/// the quantity BrickStore is tracking, which for a wanted list is the
+/// minimum quantity wanted.
+const BRICKSTORE_ELEMENTS: [(&[u8], &[u8]); 9] = [
+    (b"Inventory", b"INVENTORY"),
+    (b"Item", b"ITEM"),
+    (b"ItemID", b"ITEMID"),
+    (b"ItemTypeID", b"ITEMTYPE"),
+    (b"ColorID", b"COLOR"),
+    (b"Qty", b"MINQTY"),
+    (b"Price", b"MAXPRICE"),
+    (b"Condition", b"CONDITION"),
+    (b"Remarks", b"REMARKS"),
+];
+
+/// Rewrite a BrickStore document as a Bricklink INVENTORY by renaming the
+/// elements in `BRICKSTORE_ELEMENTS`. Everything else BrickStore records,
+/// e.g. ItemName, ColorName, or GuiState, is dropped along with its content.
+///
+/// # Arguments
+///
+/// * `xml_string`: BrickStore XML, with a BrickStoreXML root
+///
+fn brickstore_to_inventory(xml_string: &str) -> Result<String, BricklineError> {
+    let mut reader = Reader::from_str(xml_string);
+    let mut writer = Writer::new(Vec::new());
+    let mut buf = Vec::new();
+    let rename = |name: &[u8]| {
+        BRICKSTORE_ELEMENTS
+            .iter()
+            .find(|(brickstore, _)| *brickstore == name)
+            .map(|(_, bricklink)| bricklink.to_vec())
+    };
+    // Depth inside an element that is being dropped, 0 when not dropping
+    let mut skip_depth = 0;
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| BricklineError::Xml(DeError::Xml(e), locate_xml_error(xml_string)))?;
+        let renamed = match event {
+            Event::Eof => break,
+            Event::Start(_) | Event::Empty(_) | Event::End(_) | Event::Text(_)
+                if skip_depth > 0 =>
+            {
+                match event {
+                    Event::Start(_) => skip_depth += 1,
+                    Event::End(_) => skip_depth -= 1,
+                    _ => (),
+                }
+                None
+            }
+            Event::Start(e) if e.name() == b"BrickStoreXML" => None,
+            Event::End(e) if e.name() == b"BrickStoreXML" => None,
+            Event::Start(e) => match rename(e.name()) {
+                Some(name) => Some(Event::Start(BytesStart::owned_name(name))),
+                None => {
+                    skip_depth = 1;
+                    None
+                }
+            },
+            Event::End(e) => rename(e.name()).map(|name| Event::End(BytesEnd::owned(name))),
+            Event::Empty(e) => {
+                rename(e.name()).map(|name| Event::Empty(BytesStart::owned_name(name)))
+            }
+            Event::Text(e) => Some(Event::Text(e.into_owned())),
+            _ => None,
+        };
+        if let Some(event) = renamed {
+            writer
+                .write_event(event)
+                .map_err(|e| BricklineError::Xml(DeError::Xml(e), None))?;
+        }
+        buf.clear();
+    }
+    String::from_utf8(writer.into_inner())
+        .map_err(|e| BricklineError::Xml(DeError::Custom(e.to_string()), None))
+}
+
+/// Read through the XML until the reader fails, and return the line and
+/// column it had reached. Returns None if the XML reads cleanly, e.g. when
+/// deserialization failed on a missing field rather than bad syntax.
+///
+/// # Arguments
+///
+/// * `xml_string`: XML that failed to deserialize
+///
+fn locate_xml_error(xml_string: &str) -> Option<XmlPosition> {
+    let mut reader = Reader::from_str(xml_string);
+    let mut buf = Vec::new();
+    let offset = loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Eof) => return None,
+            Ok(_) => buf.clear(),
+            Err(_) => break reader.buffer_position(),
+        }
+    };
+    let consumed = &xml_string.as_bytes()[..offset.min(xml_string.len())];
+    let line = consumed.iter().filter(|&&byte| byte == b'\n').count() + 1;
+    let line_start = consumed
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    Some(XmlPosition {
+        line,
+        column: offset - line_start + 1,
+    })
+}
+
+/// Options controlling what happens to a wanted list as a command loads it
+#[derive(Debug, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Collapse duplicate ItemID/Color entries (see `collapse_duplicates`)
+    pub dedup: bool,
+    /// Warn on stderr about any Colors not in the Bricklink color catalog
+    pub validate_colors: bool,
+    /// When loading a directory, skip wanted lists that fail to load rather
+    /// than stopping at the first one
+    pub continue_on_error: bool,
+}
+
+impl LoadOptions {
+    /// Read the load options shared by commands from their arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `args`: Arguments to a command
+    ///
+    pub fn from_args(args: &ArgMatches) -> LoadOptions {
+        LoadOptions {
+            dedup: args.is_present("dedup"),
+            validate_colors: args.is_present("validate"),
+            continue_on_error: args.is_present("continue-on-error"),
+        }
+    }
+}
+
+/// Load a wanted list for a command, applying the load options. The
+/// statistics are regenerated after collapsing duplicates so they describe
+/// the list that will actually be used.
+///
+/// # Arguments
+///
+/// * `file_path`: Path to file
+/// * `load_options`: What to do with the wanted list as it's loaded
+///
+fn load_wanted_list<P: AsRef<Path>>(
+    file_path: P,
+    load_options: &LoadOptions,
+) -> Result<(WantedList, WantedListStatistics), BricklineError> {
+    let file_path = file_path.as_ref();
+    let (mut wanted_list, mut statistics) = file_to_wanted_list(file_path)?;
+    if load_options.dedup {
+        wanted_list = collapse_duplicates(&wanted_list);
+        statistics = gen_statistics(&wanted_list);
+    }
+    if load_options.validate_colors {
+        for (item_id, color) in wanted_list.validate_colors() {
+            eprintln!(
+                "Warning: {} contains {}, which is not a known Bricklink color",
+                file_path.display(),
+                format_item_color(&item_id, &Some(color))
+            );
+        }
+    }
+    Ok((wanted_list, statistics))
+}
+
+/// The wanted lists loaded from a directory, along with the files that
+/// failed to load when `LoadOptions::continue_on_error` is set
+#[derive(Debug)]
+pub struct DirectoryLoad {
+    /// Each wanted list that loaded, with its path, in file name order
+    pub loaded: Vec<(PathBuf, WantedList)>,
+    /// Each file that failed to load, with why, in file name order
+    pub failed: Vec<(PathBuf, BricklineError)>,
+}
+
+/// Load every `.xml` wanted list in a directory, in file name order, applying
+/// the load options to each. Subdirectories aren't searched, and a directory
+/// with no `.xml` files is an error. A file that fails to load is an error
+/// too, unless `continue_on_error` is set, in which case it is recorded in
+/// `DirectoryLoad::failed` and the rest are still loaded.
+///
+/// # Arguments
+///
+/// * `dir_path`: Directory of wanted lists
+/// * `load_options`: What to do with each wanted list as it's loaded
+///
+/// Example
+///
+/// ```no_run
+/// use brickline::{load_wanted_list_dir, LoadOptions};
+/// use std::path::Path;
+///
+/// let load_options = LoadOptions { continue_on_error: true, ..LoadOptions::default() };
+/// let directory_load = load_wanted_list_dir(Path::new("/path/to/lists"), &load_options).unwrap();
+/// for (path, e) in directory_load.failed.iter() {
+///     eprintln!("Skipped {}: {}", path.display(), e);
+/// }
+/// ```
+pub fn load_wanted_list_dir(
+    dir_path: &Path,
+    load_options: &LoadOptions,
+) -> Result<DirectoryLoad, BricklineError> {
+    let mut xml_paths = Vec::new();
+    for entry in std::fs::read_dir(dir_path)? {
+        let path = entry?.path();
+        let is_xml = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+        if path.is_file() && is_xml {
+            xml_paths.push(path);
+        }
+    }
+    if xml_paths.is_empty() {
+        return Err(BricklineError::Io(IOError::new(
+            ErrorKind::NotFound,
+            format!("No .xml wanted lists found in {}", dir_path.display()),
+        )));
+    }
+    xml_paths.sort();
+
+    let mut directory_load = DirectoryLoad {
+        loaded: Vec::new(),
+        failed: Vec::new(),
+    };
+    for xml_path in xml_paths {
+        match load_wanted_list(&xml_path, load_options) {
+            Ok((wanted_list, _)) => directory_load.loaded.push((xml_path, wanted_list)),
+            Err(e) if load_options.continue_on_error => directory_load.failed.push((xml_path, e)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(directory_load)
+}
+
+/// Given an WantedList, build a HashMap of each WantedList Item where
+/// the hash key is the ItemID and Color combination for the Item.
+/// Note: we explicitly .clone the Item for this map, as we're going to
+/// use it as the base case for our joined list.
+///
+/// # Arguments
+///
+/// * `inventory`: Bricklink inventory as deserialized from XML
+///
+/// Example
+///
+/// ```no_run
+/// use brickline::{xml_to_string, build_item_color_hashmap};
+/// use brickline::wanted::{WantedList, SerdeWantedList};
+/// use quick_xml::de::from_str;
+/// use std::convert::TryFrom;
+/// use std::path::PathBuf;
+///
+/// let path = PathBuf::from("/home/user/path/to/file.xml");
+/// let xml_string = xml_to_string(&path).unwrap();
+/// let wanted_list = WantedList::try_from(from_str::<SerdeWantedList>(&xml_string).unwrap()).unwrap();
+/// let hm = build_item_color_hashmap(&wanted_list);
+/// ```
+pub fn build_item_color_hashmap(inventory: &WantedList) -> BTreeMap<ItemColorHashKey<'_>, Item> {
+    build_item_hashmap(inventory, JoinKey::IdColor)
+}
+
+/// Like `build_item_color_hashmap`, but keyed at the granularity of the
+/// given JoinKey. If several Items share a key, the last one wins.
+///
+/// # Arguments
+///
+/// * `inventory`: Bricklink inventory as deserialized from XML
+/// * `join_key`: Which Item fields make up the key
+///
+fn build_item_hashmap(
+    inventory: &WantedList,
+    join_key: JoinKey,
+) -> BTreeMap<ItemColorHashKey<'_>, Item> {
+    inventory.iter().fold(BTreeMap::new(), |mut acc, item| {
+        // Cloning here as we're going to mutate these
+        // Items to combine them with other lists
+        acc.insert(join_key.key_for(item), item.clone());
+        acc
+    })
+}
+
+/// Given two items, add the MinQty of the righthand (incrementing) Item to the
+/// lefthand (to-be-incremented) Item. The lefthand item_to_increment *will*
+/// be mutated.
+///
+/// A missing MinQty on either side is an implicit 1, as Bricklink treats it,
+/// so a lefthand Item with no MinQty joined with a MinQty of 10 ends up with
+/// 11. Use `MissingMinQty::AdoptRight` in the join options to have it take
+/// the 10 instead.
+///
+/// The sum saturates at `i32::MAX` rather than overflowing, so joining two
+/// very large quantities can't wrap around to a negative MinQty.
+///
+/// With `DefaultQty::Exclude` a missing MinQty counts as 0 instead, so it
+/// adds nothing, and two Items with no MinQty stay without one.
+///
+/// # Arguments
+///
+/// * `item_to_increment`: Item to be incremented
+/// * `incrementing_item`: Item to increment from
+/// * `default_qty`: What a missing MinQty counts as
+///
+/// Example
+///
+/// use brickline::increment_item;
+/// use brickline::wanted::Item;
+///
+/// let mut left_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3039")), Some(Color(5)), Some(MinQty(20)));
+/// let right_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3039")), Some(Color(5)), Some(MinQty(10)));
+///
+/// increment_item(&mut left_item, &right_item, DefaultQty::One);
+///
+fn increment_item(item_to_increment: &mut Item, incrementing_item: &Item, default_qty: DefaultQty) {
+    if item_to_increment.min_qty.is_none()
+        && incrementing_item.min_qty.is_none()
+        && default_qty == DefaultQty::Exclude
+    {
+        return;
+    }
+    let incrementing_min_qty = default_qty.qty(&incrementing_item.min_qty);
+    let min_qty = default_qty.qty(&item_to_increment.min_qty);
+    item_to_increment.min_qty = Some(MinQty(min_qty.saturating_add(incrementing_min_qty)));
+}
+
+/// Given an WantedList, collapse any Items that share an ItemID/Color key
+/// into a single Item, summing their MinQty the same way a join does.
+/// The first Item seen for a key keeps its metadata (remarks, condition, etc.)
+/// and its position in the list, matching the join rule that the lefthand
+/// Item wins.
+///
+/// # Arguments
+///
+/// * `inventory`: WantedList that may contain duplicate ItemID/Color entries
+///
+/// Example
+///
+/// ```
+/// use brickline::collapse_duplicates;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3001")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let item_1 = item.clone();
+///
+/// let inventory = WantedList { items: vec![item, item_1] };
+/// let deduped = collapse_duplicates(&inventory);
+/// assert_eq!(deduped.items.len(), 1);
+/// assert_eq!(deduped.items[0].min_qty, Some(MinQty(40)));
+/// ```
+pub fn collapse_duplicates(inventory: &WantedList) -> WantedList {
+    collapse_duplicates_by(inventory, JoinKey::IdColor)
+}
+
+/// Like `collapse_duplicates`, but Items are duplicates when they share a
+/// key at the granularity of the given JoinKey.
+///
+/// # Arguments
+///
+/// * `inventory`: WantedList that may contain duplicate entries
+/// * `key`: Which Item fields must match for Items to be collapsed
+///
+/// Example
+///
+/// ```
+/// use brickline::{collapse_duplicates_by, JoinKey};
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let red = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3001")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut black = red.clone();
+/// black.color = Some(Color(11));
+///
+/// let inventory = WantedList { items: vec![red, black] };
+/// let deduped = collapse_duplicates_by(&inventory, JoinKey::IdOnly);
+/// assert_eq!(deduped.items.len(), 1);
+/// assert_eq!(deduped.items[0].color, Some(Color(5)));
+/// ```
+pub fn collapse_duplicates_by(inventory: &WantedList, key: JoinKey) -> WantedList {
+    let mut key_positions: BTreeMap<ItemColorHashKey, usize> = BTreeMap::new();
+    let mut items: Vec<Item> = Vec::new();
+    for item in inventory {
+        let item_color_key = key.key_for(item);
+        if let Some(position) = key_positions.get(&item_color_key) {
+            increment_item(&mut items[*position], item, DefaultQty::One);
+        } else {
+            key_positions.insert(item_color_key, items.len());
+            items.push(item.clone());
+        }
+    }
+    WantedList { items }
+}
+
+/// How to combine the MinQty of two Items with the same ItemID/Color
+/// during a join
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinStrategy {
+    /// Add the two quantities together (see `increment_item`)
+    Sum,
+    /// Take the larger quantity, treating a missing MinQty as 1. Ties keep
+    /// the left quantity.
+    Max,
+    /// Keep the left quantity
+    KeepLeft,
+    /// Take the right quantity, including a missing one
+    KeepRight,
+}
+
+impl std::str::FromStr for JoinStrategy {
+    type Err = IOError;
+
+    fn from_str(strategy_str: &str) -> Result<Self, Self::Err> {
+        match strategy_str {
+            "sum" => Ok(Self::Sum),
+            "max" => Ok(Self::Max),
+            "keep-left" => Ok(Self::KeepLeft),
+            "keep-right" => Ok(Self::KeepRight),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported join strategy", unsupported),
+            )),
+        }
+    }
+}
+
+/// How to combine the Remarks of two matching Items during a join
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RemarksPolicy {
+    /// Keep the left Remarks
+    KeepLeft,
+    /// Take the right Remarks, including missing ones
+    KeepRight,
+    /// Join the left and right Remarks with `REMARKS_SEPARATOR`. A Remark
+    /// already present on the left isn't added again, so identical Remarks
+    /// aren't repeated. If only one side has Remarks, those are kept.
+    Concatenate,
+}
+
+/// Separator used between Remarks by `RemarksPolicy::Concatenate`
+pub const REMARKS_SEPARATOR: &str = "; ";
+
+impl std::str::FromStr for RemarksPolicy {
+    type Err = IOError;
+
+    fn from_str(policy_str: &str) -> Result<Self, Self::Err> {
+        match policy_str {
+            "keep-left" => Ok(Self::KeepLeft),
+            "keep-right" => Ok(Self::KeepRight),
+            "concat" => Ok(Self::Concatenate),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported remarks policy", unsupported),
+            )),
+        }
+    }
+}
+
+/// How to combine the MaxPrice of two matching Items during a join. An
+/// Item with no MaxPrice, or Bricklink's negative "no maximum" price, counts
+/// as unpriced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PricePolicy {
+    /// Keep the left MaxPrice, even if it is unpriced
+    KeepLeft,
+    /// Take the lower of the two prices. If only one side is priced, that
+    /// price is taken.
+    Min,
+    /// Take the higher of the two prices. If only one side is priced, that
+    /// price is taken.
+    Max,
+}
+
+impl std::str::FromStr for PricePolicy {
+    type Err = IOError;
+
+    fn from_str(policy_str: &str) -> Result<Self, Self::Err> {
+        match policy_str {
+            "keep-left" => Ok(Self::KeepLeft),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported price policy", unsupported),
+            )),
+        }
+    }
+}
+
+/// What a join does when the lefthand Item has no MinQty
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MissingMinQty {
+    /// Treat the missing MinQty as 1 and combine it with the join strategy
+    ImplicitOne,
+    /// Take the righthand MinQty as it is, whatever the join strategy
+    AdoptRight,
+}
+
+impl std::str::FromStr for MissingMinQty {
+    type Err = IOError;
+
+    fn from_str(missing_str: &str) -> Result<Self, Self::Err> {
+        match missing_str {
+            "implicit-one" => Ok(Self::ImplicitOne),
+            "adopt-right" => Ok(Self::AdoptRight),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported missing MinQty rule", unsupported),
+            )),
+        }
+    }
+}
+
+/// Which fields of an Item make up its key when joining. Items with equal
+/// keys are merged.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinKey {
+    /// ItemID and Color
+    IdColor,
+    /// ItemID, Color, and Condition, so New and Used stay separate
+    IdColorCondition,
+    /// ItemID alone, merging every Color of a part. The lefthand Item keeps
+    /// its Color.
+    IdOnly,
+}
+
+impl JoinKey {
+    /// Build the join key for an Item at this granularity
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to build a key for
+    ///
+    fn key_for(self, item: &Item) -> ItemColorHashKey<'_> {
+        match self {
+            JoinKey::IdColor => ItemColorHashKey {
+                item_id: Cow::Borrowed(&item.item_id),
+                color: &item.color,
+                condition: None,
+            },
+            JoinKey::IdColorCondition => ItemColorHashKey {
+                item_id: Cow::Borrowed(&item.item_id),
+                color: &item.color,
+                condition: item.condition.as_ref(),
+            },
+            JoinKey::IdOnly => ItemColorHashKey {
+                item_id: Cow::Borrowed(&item.item_id),
+                color: &None,
+                condition: None,
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for JoinKey {
+    type Err = IOError;
+
+    fn from_str(key_str: &str) -> Result<Self, Self::Err> {
+        match key_str {
+            "id-color" => Ok(Self::IdColor),
+            "id-color-condition" => Ok(Self::IdColorCondition),
+            "id" => Ok(Self::IdOnly),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported join key", unsupported),
+            )),
+        }
+    }
+}
+
+/// The order of the Items in a joined WantedList
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinOrder {
+    /// Sorted by join key, i.e. by ItemID, then Color
+    Sorted,
+    /// The order of the lefthand list, followed by the Items only found in
+    /// the righthand list in their order
+    PreserveLeft,
+}
+
+impl std::str::FromStr for JoinOrder {
+    type Err = IOError;
+
+    fn from_str(order_str: &str) -> Result<Self, Self::Err> {
+        match order_str {
+            "sorted" => Ok(Self::Sorted),
+            "preserve-left" => Ok(Self::PreserveLeft),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported join order", unsupported),
+            )),
+        }
+    }
+}
+
+/// Options controlling how `join_inventories_with` joins two wanted lists
+#[derive(Debug, PartialEq)]
+pub struct JoinOptions {
+    /// How to combine the MinQty of matching Items
+    pub strategy: JoinStrategy,
+    /// What to do when the lefthand Item has no MinQty
+    pub missing_min_qty: MissingMinQty,
+    /// Which fields decide whether two Items match
+    pub key: JoinKey,
+    /// The order of the joined Items
+    pub order: JoinOrder,
+    /// How to combine the Remarks of matching Items
+    pub remarks: RemarksPolicy,
+    /// How to combine the MaxPrice of matching Items
+    pub price: PricePolicy,
+    /// If set, ItemIDs are normalized by stripping these suffixes before
+    /// matching (see `ItemID::normalized_with`). The joined Items keep
+    /// their original ItemIDs.
+    pub id_suffixes: Option<Vec<String>>,
+    /// Treat a colorless righthand Item as a wildcard that matches every
+    /// lefthand Item with the same ItemID, whatever its Color (see
+    /// `join_inventories_tracked`)
+    pub nocolor_wildcard: bool,
+    /// What a missing MinQty counts as when combining quantities
+    pub default_qty: DefaultQty,
+    /// If set, tag the Remarks of every Item the righthand list adds or
+    /// increments with where it came from (see `tag_source`)
+    pub source_tag: Option<String>,
+    /// Fill in the fields a lefthand Item is missing from the matching
+    /// righthand Item (see `Item::merge_fields`). This happens after the
+    /// Remarks and price policies have been applied.
+    pub fill_missing: bool,
+}
+
+impl Default for JoinOptions {
+    fn default() -> JoinOptions {
+        JoinOptions {
+            strategy: JoinStrategy::Sum,
+            missing_min_qty: MissingMinQty::ImplicitOne,
+            key: JoinKey::IdColor,
+            order: JoinOrder::Sorted,
+            remarks: RemarksPolicy::KeepLeft,
+            price: PricePolicy::KeepLeft,
+            id_suffixes: None,
+            nocolor_wildcard: false,
+            default_qty: DefaultQty::One,
+            source_tag: None,
+            fill_missing: false,
+        }
+    }
+}
+
+impl JoinOptions {
+    /// Read the join options from the arguments to the `join` command
+    ///
+    /// # Arguments
+    ///
+    /// * `join_args`: Arguments to the join command
+    ///
+    pub fn from_args(join_args: &ArgMatches) -> Result<JoinOptions, IOError> {
+        Ok(JoinOptions {
+            strategy: join_args
+                .value_of("strategy")
+                .unwrap_or("sum")
+                .parse::<JoinStrategy>()?,
+            missing_min_qty: join_args
+                .value_of("missing-qty")
+                .unwrap_or("implicit-one")
+                .parse::<MissingMinQty>()?,
+            key: join_args
+                .value_of("key")
+                .unwrap_or("id-color")
+                .parse::<JoinKey>()?,
+            order: join_args
+                .value_of("order")
+                .unwrap_or("sorted")
+                .parse::<JoinOrder>()?,
+            remarks: join_args
+                .value_of("remarks")
+                .unwrap_or("keep-left")
+                .parse::<RemarksPolicy>()?,
+            price: join_args
+                .value_of("price-policy")
+                .unwrap_or("keep-left")
+                .parse::<PricePolicy>()?,
+            id_suffixes: if join_args.is_present("normalize-ids") {
+                Some(match join_args.value_of("id-suffixes") {
+                    Some(suffixes) => suffixes.split(',').map(String::from).collect(),
+                    None => DEFAULT_ID_SUFFIXES
+                        .iter()
+                        .map(|s| String::from(*s))
+                        .collect(),
+                })
+            } else {
+                None
+            },
+            nocolor_wildcard: join_args.is_present("treat-nocolor-as-wildcard"),
+            default_qty: join_args
+                .value_of("default-qty")
+                .unwrap_or("one")
+                .parse::<DefaultQty>()?,
+            source_tag: if join_args.is_present("tag-source") {
+                join_args.value_of("right").map(source_name)
+            } else {
+                None
+            },
+            fill_missing: join_args.is_present("fill-missing"),
+        })
+    }
+
+    /// Build the join key for an Item, normalizing its ItemID if asked to
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to build a key for
+    ///
+    fn key_for<'a>(&self, item: &'a Item) -> ItemColorHashKey<'a> {
+        let mut item_color_key = self.key.key_for(item);
+        if let Some(suffixes) = &self.id_suffixes {
+            item_color_key.item_id = Cow::Owned(item.item_id.normalized_with(suffixes));
+        }
+        item_color_key
+    }
+
+    /// Build the owned join key for an Item, e.g. to look it up in the
+    /// JoinChanges of a join made with these options
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to build a key for
+    ///
+    pub fn owned_key_for(&self, item: &Item) -> OwnedJoinKey {
+        OwnedJoinKey::from(&self.key_for(item))
+    }
+}
+
+/// Combine the MinQty of the righthand Item into the lefthand Item according
+/// to the join strategy. Only MinQty is touched; the lefthand Item keeps
+/// all of its other metadata.
+///
+/// # Arguments
+///
+/// * `left_item`: Item to be merged into
+/// * `right_item`: Item to merge from
+/// * `strategy`: How to combine the two MinQty values
+///
+fn merge_min_qty(
+    left_item: &mut Item,
+    right_item: &Item,
+    strategy: JoinStrategy,
+    default_qty: DefaultQty,
+) {
+    match strategy {
+        JoinStrategy::Sum => increment_item(left_item, right_item, default_qty),
+        JoinStrategy::Max => {
+            let left_qty = default_qty.qty(&left_item.min_qty);
+            let right_qty = default_qty.qty(&right_item.min_qty);
+            if right_qty > left_qty {
+                left_item.min_qty = right_item.min_qty.clone();
+            }
+        }
+        JoinStrategy::KeepLeft => (),
+        JoinStrategy::KeepRight => left_item.min_qty = right_item.min_qty.clone(),
+    }
+}
+
+/// Combine the Remarks of the righthand Item into the lefthand Item according
+/// to the remarks policy.
+///
+/// # Arguments
+///
+/// * `left_item`: Item to be merged into
+/// * `right_item`: Item to merge from
+/// * `policy`: How to combine the two Remarks
+///
+fn merge_remarks(left_item: &mut Item, right_item: &Item, policy: RemarksPolicy) {
+    match policy {
+        RemarksPolicy::KeepLeft => (),
+        RemarksPolicy::KeepRight => left_item.remarks = right_item.remarks.clone(),
+        RemarksPolicy::Concatenate => match (&mut left_item.remarks, &right_item.remarks) {
+            (Some(left_remarks), Some(right_remarks)) => {
+                let already_present = left_remarks
+                    .0
+                    .split(REMARKS_SEPARATOR)
+                    .any(|remark| remark == right_remarks.0);
+                if !already_present {
+                    left_remarks.0.push_str(REMARKS_SEPARATOR);
+                    left_remarks.0.push_str(&right_remarks.0);
+                }
+            }
+            (None, Some(_)) => left_item.remarks = right_item.remarks.clone(),
+            (_, None) => (),
+        },
+    }
+}
+
+/// Append `[from <source>]` to the Remarks of an Item, after a space if it
+/// already has Remarks. An Item already tagged with the source is left
+/// alone, so it is tagged once however many times the source matches it.
+///
+/// # Arguments
+///
+/// * `item`: Item to tag
+/// * `source`: Name of the wanted list the Item came from
+///
+/// Example
+///
+/// ```
+/// use brickline::tag_source;
+/// use brickline::wanted::{Item, ItemID, ItemType, Remarks};
+///
+/// let mut item = Item::build_test_item(ItemType::Part, ItemID(String::from("3039")), None, None);
+/// item.remarks = Some(Remarks(String::from("Castle")));
+/// tag_source(&mut item, "list2");
+/// assert_eq!(item.remarks, Some(Remarks(String::from("Castle [from list2]"))));
+/// ```
+pub fn tag_source(item: &mut Item, source: &str) {
+    let tag = format!("[from {}]", source);
+    match &mut item.remarks {
+        Some(remarks) if remarks.0.contains(&tag) => (),
+        Some(remarks) if !remarks.0.is_empty() => {
+            remarks.0.push(' ');
+            remarks.0.push_str(&tag);
+        }
+        _ => item.remarks = Some(Remarks(tag)),
+    }
+}
+
+/// The name a wanted list is tagged with by `tag_source`: its file name
+/// without the extension
+///
+/// # Arguments
+///
+/// * `file_path`: String path to the wanted list
+///
+fn source_name(file_path: &str) -> String {
+    Path::new(file_path).file_stem().map_or_else(
+        || String::from(file_path),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
+
+/// Combine the MaxPrice of the righthand Item into the lefthand Item
+/// according to the price policy.
+///
+/// # Arguments
+///
+/// * `left_item`: Item to be merged into
+/// * `right_item`: Item to merge from
+/// * `policy`: How to combine the two MaxPrices
+///
+fn merge_max_price(left_item: &mut Item, right_item: &Item, policy: PricePolicy) {
+    let priced = |item: &Item| {
+        item.max_price
+            .as_ref()
+            .filter(|price| price.0 >= 0)
+            .cloned()
+    };
+    let (left_price, right_price) = (priced(left_item), priced(right_item));
+    let merged_price = match (policy, left_price, right_price) {
+        (PricePolicy::KeepLeft, _, _) => return,
+        (PricePolicy::Min, Some(left), Some(right)) => MaxPrice(left.0.min(right.0)),
+        (PricePolicy::Max, Some(left), Some(right)) => MaxPrice(left.0.max(right.0)),
+        (_, Some(price), None) | (_, None, Some(price)) => price,
+        (_, None, None) => return,
+    };
+    left_item.max_price = Some(merged_price);
+}
+
+/// Given two Inventories, join the right inventory into the left one.
+/// Here's how the join happens:
+/// 1. Build hash table from left inventory
+/// 2. Iterate through right inventory and probe table for ItemId/Color keys
+/// 3. If a key is found, add the MinQty of the right inventory to the left.
+///    NOTE: The metadata from the *left* inventory is retained. There is no
+///    other metadata joining other than MinQty (and Remarks or MaxPrice, if
+///    a `RemarksPolicy` or `PricePolicy` other than `KeepLeft` is given to
+///    `join_inventories_with`).
+/// 4. If no key is found, add the Item from the right inventory to the hash table
+/// 5. Convert the .values() of the hash table into .items of a new WantedList
+///
+/// This is `join_inventories_with` using the default `JoinOptions`.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to be joined into
+/// * `right_inventory`: WantedList to join into left inventory
+///
+/// Example
+///
+/// ```
+/// use brickline::join_inventories;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let item_1 = item.clone();
+///
+/// let left_inventory = WantedList { items: vec![item] };
+/// let right_inventory = WantedList { items: vec![item_1] };
+///
+/// let joined_inventory = join_inventories(&left_inventory, &right_inventory);
+/// ```
+pub fn join_inventories(left_inventory: &WantedList, right_inventory: &WantedList) -> WantedList {
+    join_inventories_with(left_inventory, right_inventory, &JoinOptions::default())
+}
+
+/// Like `join_inventories`, but takes ownership of both inventories so that
+/// no Items are cloned; matching righthand Items are merged in and then
+/// dropped. This is worth it for very large lists. The result is identical
+/// to `join_inventories`.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to be joined into
+/// * `right_inventory`: WantedList to join into left inventory
+///
+/// Example
+///
+/// ```
+/// use brickline::join_inventories_owned;
+/// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+///
+/// let test_item = Item::build_test_item(
+///     ItemType::Part,
+///     ItemID(String::from("3622")),
+///     Some(Color(11)),
+///     Some(MinQty(2))
+/// );
+/// let left_inventory = WantedList { items: vec![test_item.clone()] };
+/// let right_inventory = WantedList { items: vec![test_item] };
+/// let joined = join_inventories_owned(left_inventory, right_inventory);
+/// assert_eq!(joined.items[0].min_qty, Some(MinQty(4)));
+/// ```
+pub fn join_inventories_owned(
+    left_inventory: WantedList,
+    right_inventory: WantedList,
+) -> WantedList {
+    // Keyed on owned copies of the ItemID and Color, which are much smaller
+    // than the Items, so the Items can be moved around freely
+    let mut items: Vec<Item> =
+        Vec::with_capacity(left_inventory.items.len() + right_inventory.items.len());
+    let mut positions: BTreeMap<(ItemID, Option<Color>), usize> = BTreeMap::new();
+    for left_item in left_inventory.items {
+        let item_color_key = left_item.color_key();
+        if let Some(&position) = positions.get(&item_color_key) {
+            items[position] = left_item;
+        } else {
+            positions.insert(item_color_key, items.len());
+            items.push(left_item);
+        }
+    }
+    for right_item in right_inventory.items {
+        let item_color_key = right_item.color_key();
+        if let Some(&position) = positions.get(&item_color_key) {
+            increment_item(&mut items[position], &right_item, DefaultQty::One);
+        } else {
+            positions.insert(item_color_key, items.len());
+            items.push(right_item);
+        }
+    }
+    let mut slots: Vec<Option<Item>> = items.into_iter().map(Some).collect();
+    WantedList {
+        items: positions
+            .values()
+            .filter_map(|&position| slots[position].take())
+            .collect(),
+    }
+}
+
+/// Join any number of wanted lists by folding `join_inventories` over them
+/// from left to right, so metadata comes from the first list an Item
+/// appears in. Joining no lists gives an empty WantedList.
+///
+/// # Arguments
+///
+/// * `lists`: WantedLists to join
+///
+/// Example
+///
+/// ```
+/// use brickline::join_many;
+/// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+///
+/// let test_item = Item::build_test_item(
+///     ItemType::Part,
+///     ItemID(String::from("3622")),
+///     Some(Color(11)),
+///     Some(MinQty(2))
+/// );
+/// let wanted_list = WantedList { items: vec![test_item] };
+/// let joined = join_many(&[wanted_list.clone(), wanted_list.clone(), wanted_list]);
+/// assert_eq!(joined.items[0].min_qty, Some(MinQty(6)));
+/// ```
+pub fn join_many(lists: &[WantedList]) -> WantedList {
+    lists.iter().enumerate().fold(
+        WantedList { items: Vec::new() },
+        |joined, (i, wanted_list)| {
+            let joined = join_inventories(&joined, wanted_list);
+            verbose!(
+                "Joined list {} of {} ({} items so far)",
+                i + 1,
+                lists.len(),
+                joined.len()
+            );
+            joined
+        },
+    )
+}
+
+/// Like `join_many`, but each list is named, and every Item a list adds or
+/// increments is tagged with its name (see `tag_source`). As the first list
+/// is joined into an empty one, all of its Items are tagged too.
+///
+/// # Arguments
+///
+/// * `lists`: WantedLists to join, each with the name to tag its Items with
+///
+/// Example
+///
+/// ```
+/// use brickline::join_many_tagged;
+/// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Remarks};
+///
+/// let test_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, None);
+/// let wanted_list = WantedList { items: vec![test_item] };
+/// let joined = join_many_tagged(&[(String::from("castle"), wanted_list)]);
+/// assert_eq!(joined.items[0].remarks, Some(Remarks(String::from("[from castle]"))));
+/// ```
+pub fn join_many_tagged(lists: &[(String, WantedList)]) -> WantedList {
+    lists.iter().enumerate().fold(
+        WantedList { items: Vec::new() },
+        |joined, (i, (source, wanted_list))| {
+            let join_options = JoinOptions {
+                source_tag: Some(source.clone()),
+                ..JoinOptions::default()
+            };
+            let joined = join_inventories_with(&joined, wanted_list, &join_options);
+            verbose!(
+                "Joined list {} of {} ({} items so far)",
+                i + 1,
+                lists.len(),
+                joined.len()
+            );
+            joined
+        },
+    )
+}
+
+/// Given two Inventories, join the right inventory into the left one,
+/// matching Items on the key and combining their MinQty with the strategy
+/// from the join options. Otherwise this works exactly like
+/// `join_inventories`.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to be joined into
+/// * `right_inventory`: WantedList to join into left inventory
+/// * `join_options`: How to match and combine Items
+///
+/// Example
+///
+/// ```
+/// use brickline::{join_inventories_with, JoinOptions, JoinStrategy};
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let item_1 = item.clone();
+///
+/// let left_inventory = WantedList { items: vec![item] };
+/// let right_inventory = WantedList { items: vec![item_1] };
+///
+/// let join_options = JoinOptions {
+///     strategy: JoinStrategy::Max,
+///     ..JoinOptions::default()
+/// };
+/// let joined_inventory =
+///     join_inventories_with(&left_inventory, &right_inventory, &join_options);
+/// assert_eq!(joined_inventory.items[0].min_qty, Some(MinQty(20)));
+/// ```
+pub fn join_inventories_with(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+    join_options: &JoinOptions,
+) -> WantedList {
+    join_inventories_tracked(left_inventory, right_inventory, join_options).0
+}
+
+/// What a join did to the lefthand WantedList, keyed by the join key in use
+/// (see `JoinOptions::owned_key_for`)
+#[derive(Debug, Default, PartialEq)]
+pub struct JoinChanges {
+    /// Lefthand Items whose MinQty was changed by merging in a righthand Item
+    pub incremented: BTreeSet<OwnedJoinKey>,
+    /// Righthand Items with no lefthand match, newly added to the list
+    pub added: BTreeSet<OwnedJoinKey>,
+}
+
+/// Like `join_inventories_with`, but also reports which Items had their
+/// MinQty changed by the join and which were newly added from the
+/// righthand list.
+///
+/// With `nocolor_wildcard` set, a colorless righthand Item is merged into
+/// every lefthand Item with the same ItemID (and Condition, if that's part
+/// of the key), including a colorless one. This is ambiguous on purpose:
+/// the righthand quantity is combined into each variant rather than split
+/// between them, so with `JoinStrategy::Sum` it is counted once per color.
+/// Only righthand Items act as wildcards, and a colorless righthand Item
+/// with no lefthand variants is added as usual.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to be joined into
+/// * `right_inventory`: WantedList to join into left inventory
+/// * `join_options`: How to match and combine Items
+///
+/// With `JoinOptions::source_tag` set, every Item the righthand list adds,
+/// and every Item whose MinQty it changes, is tagged with `tag_source`.
+///
+/// Example
+///
+/// ```
+/// use brickline::{join_inventories_tracked, JoinOptions};
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let left_inventory = WantedList { items: vec![item.clone()] };
+/// let right_inventory = WantedList { items: vec![item] };
+///
+/// let join_options = JoinOptions::default();
+/// let (_, changes) = join_inventories_tracked(&left_inventory, &right_inventory, &join_options);
+/// assert!(changes.incremented.contains(&join_options.owned_key_for(&left_inventory.items[0])));
+/// assert!(changes.added.is_empty());
+/// ```
+pub fn join_inventories_tracked(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+    join_options: &JoinOptions,
+) -> (WantedList, JoinChanges) {
+    let mut changes = JoinChanges::default();
+    // Joined Items in insertion order, along with the position of each key
+    // in that list. If the left inventory repeats a key, the last Item wins.
+    let mut items: Vec<Item> = Vec::new();
+    let mut positions: BTreeMap<ItemColorHashKey<'_>, usize> = BTreeMap::new();
+    for left_item in left_inventory {
+        let item_color_key = join_options.key_for(left_item);
+        if let Some(&position) = positions.get(&item_color_key) {
+            items[position] = left_item.clone();
+        } else {
+            positions.insert(item_color_key, items.len());
+            items.push(left_item.clone());
+        }
+    }
+    for right_item in right_inventory {
+        let item_color_key = join_options.key_for(right_item);
+        let matched_positions: Vec<usize> =
+            if join_options.nocolor_wildcard && right_item.color.is_none() {
+                positions
+                    .iter()
+                    .filter(|(key, _)| key.matches_any_color(&item_color_key))
+                    .map(|(_, &position)| position)
+                    .collect()
+            } else {
+                positions
+                    .get(&item_color_key)
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            };
+        for &position in matched_positions.iter() {
+            let left_item = &mut items[position];
+            let original_min_qty = left_item.min_qty.clone();
+            if left_item.min_qty.is_none()
+                && join_options.missing_min_qty == MissingMinQty::AdoptRight
+            {
+                left_item.min_qty = right_item.min_qty.clone();
+            } else {
+                merge_min_qty(
+                    left_item,
+                    right_item,
+                    join_options.strategy,
+                    join_options.default_qty,
+                );
+            }
+            merge_remarks(left_item, right_item, join_options.remarks);
+            merge_max_price(left_item, right_item, join_options.price);
+            if join_options.fill_missing {
+                left_item.merge_fields(right_item);
+            }
+            let left_key = join_options.owned_key_for(left_item);
+            if left_item.min_qty != original_min_qty {
+                if let Some(source) = &join_options.source_tag {
+                    tag_source(left_item, source);
+                }
+                if !changes.added.contains(&left_key) {
+                    changes.incremented.insert(left_key);
+                }
+            }
+        }
+        if matched_positions.is_empty() {
+            let mut added_item = right_item.clone();
+            if let Some(source) = &join_options.source_tag {
+                tag_source(&mut added_item, source);
+            }
+            changes.added.insert(OwnedJoinKey::from(&item_color_key));
+            positions.insert(item_color_key, items.len());
+            items.push(added_item);
+        }
+    }
+    let items = match join_options.order {
+        JoinOrder::Sorted => positions
+            .values()
+            .map(|&position| items[position].clone())
+            .collect(),
+        JoinOrder::PreserveLeft => items,
+    };
+    (WantedList { items }, changes)
+}
+
+/// Criteria for selecting Items out of a WantedList. Every criterion that
+/// is set must match for an Item to be selected; unset criteria match
+/// everything.
+#[derive(Debug, Default, PartialEq)]
+pub struct ItemFilter {
+    pub color: Option<Color>,
+    pub item_type: Option<ItemType>,
+    pub min_qty_gte: Option<i32>,
+    /// Only keep Items whose MaxPrice is at most this much
+    pub max_price_lte: Option<MaxPrice>,
+    /// Whether Items with no MaxPrice (or Bricklink's negative "no maximum"
+    /// price) pass the `max_price_lte` criterion
+    pub include_unpriced: bool,
+}
+
+impl ItemFilter {
+    /// Does the Item match every criterion set on this filter? An Item with
+    /// no MinQty is treated as wanting 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to check
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use brickline::ItemFilter;
+    /// use brickline::wanted::{Item, ItemID, ItemType, Color, MinQty};
+    ///
+    /// let item = Item::build_test_item(
+    ///       ItemType::Part,
+    ///       ItemID(String::from("3039")),
+    ///       Some(Color(5)),
+    ///       Some(MinQty(20)),
+    /// );
+    /// let item_filter = ItemFilter {
+    ///     color: Some(Color(5)),
+    ///     min_qty_gte: Some(10),
+    ///     ..ItemFilter::default()
+    /// };
+    /// assert!(item_filter.matches(&item));
+    /// ```
+    pub fn matches(&self, item: &Item) -> bool {
+        let color_matches = match &self.color {
+            Some(color) => item.color.as_ref() == Some(color),
+            None => true,
+        };
+        let item_type_matches = match &self.item_type {
+            Some(item_type) => &item.item_type == item_type,
+            None => true,
+        };
+        let min_qty_matches = match self.min_qty_gte {
+            Some(threshold) => item.min_qty.as_ref().map_or(1, |qty| qty.0) >= threshold,
+            None => true,
+        };
+        let max_price_matches = match &self.max_price_lte {
+            Some(threshold) => match &item.max_price {
+                Some(max_price) if max_price.0 >= 0 => max_price.0 <= threshold.0,
+                _ => self.include_unpriced,
+            },
+            None => true,
+        };
+        color_matches && item_type_matches && min_qty_matches && max_price_matches
+    }
+}
+
+/// A MinQty change for an ItemID/Color present in both wanted lists
+#[derive(Debug, PartialEq)]
+pub struct QtyChange {
+    pub item_id: ItemID,
+    pub color: Option<Color>,
+    pub old_qty: Option<MinQty>,
+    pub new_qty: Option<MinQty>,
+}
+
+/// The differences between two wanted lists, keyed by ItemID/Color.
+/// Items in each Vec are ordered by ItemID, then Color.
+#[derive(Debug, PartialEq)]
+pub struct WantedListDiff {
+    pub added: Vec<Item>,
+    pub removed: Vec<Item>,
+    pub changed: Vec<QtyChange>,
+}
+
+impl WantedListDiff {
+    /// Were there no differences between the two wanted lists?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Format a Color by its Bricklink name, e.g. `Red`, falling back to its
+/// ID, e.g. `Color 9999`, when it isn't in the catalog
+fn format_color(color: &Color) -> String {
+    match color.name() {
+        Some(name) => String::from(name),
+        None => format!("Color {}", color.0),
+    }
+}
+
+/// Format an ItemID/Color pair for human consumption, e.g. `3001 (Red)`
+fn format_item_color(item_id: &ItemID, color: &Option<Color>) -> String {
+    match color {
+        Some(c) => format!("{} ({})", item_id.0, format_color(c)),
+        None => format!("{} (No Color)", item_id.0),
+    }
+}
+
+/// Format a MinQty, making an absent value explicit
+fn format_min_qty(min_qty: &Option<MinQty>) -> String {
+    match min_qty {
+        Some(qty) => qty.0.to_string(),
+        None => String::from("unset"),
+    }
+}
+
+impl std::fmt::Display for WantedListDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Added Items: {}", self.added.len())?;
+        for item in &self.added {
+            writeln!(
+                f,
+                "  + {}: {}",
+                format_item_color(&item.item_id, &item.color),
+                format_min_qty(&item.min_qty)
+            )?;
+        }
+        writeln!(f, "Removed Items: {}", self.removed.len())?;
+        for item in &self.removed {
+            writeln!(
+                f,
+                "  - {}: {}",
+                format_item_color(&item.item_id, &item.color),
+                format_min_qty(&item.min_qty)
+            )?;
+        }
+        writeln!(f, "Quantity Changes: {}", self.changed.len())?;
+        for change in &self.changed {
+            writeln!(
+                f,
+                "  ~ {}: {} -> {}",
+                format_item_color(&change.item_id, &change.color),
+                format_min_qty(&change.old_qty),
+                format_min_qty(&change.new_qty)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Given two Inventories, find what changed going from the left inventory
+/// to the right one:
+/// 1. Items whose ItemID/Color key is only in the right inventory are *added*
+/// 2. Items whose ItemID/Color key is only in the left inventory are *removed*
+/// 3. Items in both whose MinQty differs are *changed*. This includes the case
+///    where only one side has a MinQty at all.
+///
+/// # Arguments
+///
+/// * `left_inventory`: The original WantedList
+/// * `right_inventory`: The WantedList to compare against the original
+///
+/// Example
+///
+/// ```
+/// use brickline::diff_inventories;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let left_item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut right_item = left_item.clone();
+/// right_item.min_qty = Some(MinQty(30));
+///
+/// let left_inventory = WantedList { items: vec![left_item] };
+/// let right_inventory = WantedList { items: vec![right_item] };
+///
+/// let diff = diff_inventories(&left_inventory, &right_inventory);
+/// assert_eq!(diff.changed.len(), 1);
+/// ```
+pub fn diff_inventories(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+) -> WantedListDiff {
+    let left_inv_map = build_item_color_hashmap(left_inventory);
+    let right_inv_map = build_item_color_hashmap(right_inventory);
+
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    for (key, left_item) in left_inv_map.iter() {
+        match right_inv_map.get(key) {
+            Some(right_item) if right_item.min_qty != left_item.min_qty => {
+                changed.push(QtyChange {
+                    item_id: left_item.item_id.clone(),
+                    color: left_item.color.clone(),
+                    old_qty: left_item.min_qty.clone(),
+                    new_qty: right_item.min_qty.clone(),
+                })
+            }
+            Some(_) => (),
+            None => removed.push(left_item.clone()),
+        }
+    }
+
+    let added = right_inv_map
+        .iter()
+        .filter(|(key, _)| !left_inv_map.contains_key(key))
+        .map(|(_, right_item)| right_item.clone())
+        .collect();
+
+    WantedListDiff {
+        added,
+        removed,
+        changed,
+    }
+}
+
+/// Given two Inventories, find the signed difference in MinQty for every
+/// ItemID/Color key in either of them: positive where the right inventory
+/// wants more than the left, negative where it wants fewer, and 0 where they
+/// agree. A key only on one side counts as 0 on the other. Repeated keys are
+/// summed, and a missing MinQty counts as 1. This is the numeric view of
+/// `diff_inventories`, e.g. for loading into a spreadsheet.
+///
+/// # Arguments
+///
+/// * `left_inventory`: The original WantedList
+/// * `right_inventory`: The WantedList to compare against the original
+///
+/// Example
+///
+/// ```
+/// use brickline::quantity_delta;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty, OwnedItemColorHashKey};
+///
+/// let left_item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut right_item = left_item.clone();
+/// right_item.min_qty = Some(MinQty(30));
+///
+/// let left_inventory = WantedList { items: vec![left_item] };
+/// let right_inventory = WantedList { items: vec![right_item.clone()] };
+///
+/// let delta = quantity_delta(&left_inventory, &right_inventory);
+/// assert_eq!(delta[&OwnedItemColorHashKey::from(&right_item)], 10);
+/// ```
+pub fn quantity_delta(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+) -> BTreeMap<OwnedItemColorHashKey, i32> {
+    let mut delta = BTreeMap::new();
+    for item in left_inventory {
+        let qty = delta.entry(OwnedItemColorHashKey::from(item)).or_insert(0);
+        *qty = i32::saturating_sub(*qty, DefaultQty::One.qty(&item.min_qty));
+    }
+    for item in right_inventory {
+        let qty = delta.entry(OwnedItemColorHashKey::from(item)).or_insert(0);
+        *qty = i32::saturating_add(*qty, DefaultQty::One.qty(&item.min_qty));
+    }
+    delta
+}
+
+/// Given two Inventories, keep only the Items whose ItemID/Color key is in
+/// both of them. Each kept Item's MinQty is the smaller of the two, treating
+/// a missing MinQty as 1. As with the join, the metadata comes from the
+/// *left* inventory, and the result is ordered by ItemID/Color.
+///
+/// Duplicate ItemID/Color entries on either side are collapsed first with
+/// `collapse_duplicates`, so their quantities are summed and the first
+/// entry keeps its metadata.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to take Items and metadata from
+/// * `right_inventory`: WantedList to intersect with
+///
+/// Example
+///
+/// ```
+/// use brickline::intersect_inventories;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let left_item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut right_item = left_item.clone();
+/// right_item.min_qty = Some(MinQty(5));
+///
+/// let left_inventory = WantedList { items: vec![left_item] };
+/// let right_inventory = WantedList { items: vec![right_item] };
+///
+/// let intersected = intersect_inventories(&left_inventory, &right_inventory);
+/// assert_eq!(intersected.items[0].min_qty, Some(MinQty(5)));
+/// ```
+pub fn intersect_inventories(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+) -> WantedList {
+    let collapsed_left = collapse_duplicates(left_inventory);
+    let collapsed_right = collapse_duplicates(right_inventory);
+    let right_inv_map = build_item_color_hashmap(&collapsed_right);
+    let items = build_item_color_hashmap(&collapsed_left)
+        .into_iter()
+        .filter_map(|(key, mut left_item)| {
+            let right_item = right_inv_map.get(&key)?;
+            let left_qty = left_item.min_qty.as_ref().map_or(1, |qty| qty.0);
+            let right_qty = right_item.min_qty.as_ref().map_or(1, |qty| qty.0);
+            if right_qty < left_qty {
+                left_item.min_qty = right_item.min_qty.clone();
+            }
+            Some(left_item)
+        })
+        .collect();
+    WantedList { items }
+}
+
+/// Items to leave out of a joined WantedList, e.g. parts sourced elsewhere.
+/// An ItemID on its own excludes it in every Color; an ItemID followed by a
+/// Color ID excludes just that Color.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExcludeList {
+    item_ids: BTreeSet<ItemID>,
+    item_colors: BTreeSet<(ItemID, Color)>,
+}
+
+impl ExcludeList {
+    /// Should the Item be left out?
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to check
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use brickline::ExcludeList;
+    /// use brickline::wanted::{Item, ItemID, ItemType, Color};
+    ///
+    /// let exclude_list = "3001\n3622 11\n".parse::<ExcludeList>().unwrap();
+    /// let item = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), Some(Color(5)), None);
+    /// assert!(!exclude_list.excludes(&item));
+    /// ```
+    pub fn excludes(&self, item: &Item) -> bool {
+        self.item_ids.contains(&item.item_id)
+            || item.color.as_ref().is_some_and(|color| {
+                self.item_colors
+                    .contains(&(item.item_id.clone(), color.clone()))
+            })
+    }
+}
+
+/// Parse newline delimited exclusions, each an ItemID optionally followed by
+/// whitespace and a Color ID. Blank lines are skipped.
+impl std::str::FromStr for ExcludeList {
+    type Err = IOError;
+
+    fn from_str(exclude_str: &str) -> Result<Self, Self::Err> {
+        let mut exclude_list = ExcludeList::default();
+        for line in exclude_str.lines() {
+            let mut fields = line.split_whitespace();
+            let item_id = match fields.next() {
+                Some(item_id) => ItemID(String::from(item_id)),
+                None => continue,
+            };
+            match (fields.next(), fields.next()) {
+                (None, _) => {
+                    exclude_list.item_ids.insert(item_id);
+                }
+                (Some(color), None) => {
+                    let color = color.parse::<i32>().map_err(|_| {
+                        IOError::new(
+                            ErrorKind::InvalidInput,
+                            format!("{} is not a valid color ID in exclusion {}", color, line),
+                        )
+                    })?;
+                    exclude_list.item_colors.insert((item_id, Color(color)));
+                }
+                _ => {
+                    return Err(IOError::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Exclusion {} should be an ItemID and an optional color",
+                            line
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(exclude_list)
+    }
+}
+
+/// Prices from a Bricklink price guide CSV export, keyed on ItemID/Color,
+/// for filling in the MaxPrice of a WantedList (see `apply_price_guide`)
+#[derive(Debug, Default, PartialEq)]
+pub struct PriceGuide {
+    prices: BTreeMap<(ItemID, Option<Color>), MaxPrice>,
+}
+
+/// A percentage markup on guide prices, kept in hundredths of a percent so
+/// that marking up is exact, e.g. `Markup(1250)` is 12.5%. Parse one from a
+/// decimal percentage like `"12.5"`. A negative markup is a discount, down
+/// to just over -100%.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Markup(pub i64);
+
+impl std::str::FromStr for Markup {
+    type Err = IOError;
+
+    fn from_str(markup_str: &str) -> Result<Self, Self::Err> {
+        // Hundredths of a percent are parsed just like cents
+        MaxPrice::parse_cents(markup_str)
+            .filter(|hundredths| *hundredths > -10000)
+            .map(Markup)
+            .ok_or(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported markup percentage", markup_str),
+            ))
+    }
+}
+
+impl PriceGuide {
+    /// Mark up every price in the guide by a percentage, then round it to
+    /// the nearest multiple of `round_to`, e.g. 0.05, or to the nearest
+    /// cent if there's no increment. The arithmetic is exact and rounds
+    /// halves up, so 0.50 marked up 5% and rounded to 0.05 is always 0.55.
+    /// Negative prices, Bricklink's "no maximum", are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `markup`: Percentage to mark each price up by
+    /// * `round_to`: Increment to round each price to, which must be positive
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use brickline::{Markup, PriceGuide};
+    /// use brickline::wanted::{Color, Item, ItemID, ItemType, MaxPrice};
+    ///
+    /// let guide = "3001,5,0.50".parse::<PriceGuide>().unwrap();
+    /// let guide = guide.adjusted(Markup(500), Some(&MaxPrice(5)));
+    /// let item = Item::build_test_item(ItemType::Part, ItemID::from("3001"), Some(Color(5)), None);
+    /// assert_eq!(guide.price_for(&item), Some(&MaxPrice(55)));
+    /// ```
+    pub fn adjusted(mut self, markup: Markup, round_to: Option<&MaxPrice>) -> PriceGuide {
+        // Work in ten-thousandths of a cent so there's only one rounding
+        let increment = i128::from(round_to.map_or(1, |round_to| round_to.0)) * 10000;
+        for price in self.prices.values_mut() {
+            if price.0 < 0 {
+                continue;
+            }
+            let scaled = i128::from(price.0) * i128::from(10000 + markup.0);
+            let rounded = (scaled + increment / 2) / increment * (increment / 10000);
+            price.0 = i64::try_from(rounded).unwrap_or(i64::MAX);
+        }
+        self
+    }
+
+    /// The guide price for an Item's ItemID/Color, if there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `item`: Item to look up
+    ///
+    pub fn price_for(&self, item: &Item) -> Option<&MaxPrice> {
+        self.prices.get(&item.color_key())
+    }
+}
+
+/// Parse a price guide CSV with an ItemID, Color ID, and average price on
+/// each line, e.g. `3001,5,0.12`. The Color may be left empty for colorless
+/// Items. A header line starting with `ItemID` and blank lines are skipped.
+impl std::str::FromStr for PriceGuide {
+    type Err = IOError;
+
+    fn from_str(guide_str: &str) -> Result<Self, Self::Err> {
+        let mut price_guide = PriceGuide::default();
+        for line in guide_str.lines() {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim()).collect();
+            if fields.len() == 1 && fields[0].is_empty() {
+                continue;
+            }
+            if fields[0].eq_ignore_ascii_case("itemid") {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                IOError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Price guide line {} {}", line, reason),
+                )
+            };
+            if fields.len() != 3 {
+                return Err(invalid("should be an ItemID, color, and price"));
+            }
+            let color = match fields[1] {
+                "" => None,
+                color => Some(Color(
+                    color
+                        .parse::<i32>()
+                        .map_err(|_| invalid("has an invalid color ID"))?,
+                )),
+            };
+            let price = MaxPrice::try_from(String::from(fields[2]))
+                .map_err(|_| invalid("has an invalid price"))?;
+            price_guide
+                .prices
+                .insert((ItemID(String::from(fields[0])), color), price);
+        }
+        Ok(price_guide)
+    }
+}
+
+/// Set the MaxPrice of every Item in the WantedList that has a price in the
+/// guide, matching on ItemID/Color. Items not in the guide keep their
+/// MaxPrice. Returns how many Items were priced.
+///
+/// # Arguments
+///
+/// * `list`: WantedList to price
+/// * `guide`: Prices to apply
+///
+/// Example
+///
+/// ```
+/// use brickline::{apply_price_guide, PriceGuide};
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MaxPrice};
+///
+/// let guide = "ItemID,Color,AvgPrice\n3001,5,0.12\n".parse::<PriceGuide>().unwrap();
+/// let item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), None);
+/// let mut wanted_list = WantedList { items: vec![item] };
+/// assert_eq!(apply_price_guide(&mut wanted_list, &guide), 1);
+/// assert_eq!(wanted_list.items[0].max_price, Some(MaxPrice(12)));
+/// ```
+pub fn apply_price_guide(list: &mut WantedList, guide: &PriceGuide) -> usize {
+    let mut priced = 0;
+    for item in list.items.iter_mut() {
+        if let Some(price) = guide.price_for(item) {
+            item.max_price = Some(price.clone());
+            priced += 1;
+        }
+    }
+    priced
+}
+
+/// How `subtract_inventories` takes a righthand Item's quantity off the
+/// matching lefthand Item
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubtractMode {
+    /// Decrease the MinQty, dropping the Item once nothing is left
+    Qty,
+    /// Increase the QtyFilled, so the original MinQty stays visible
+    Filled,
+}
+
+impl std::str::FromStr for SubtractMode {
+    type Err = IOError;
+
+    fn from_str(mode_str: &str) -> Result<Self, Self::Err> {
+        match mode_str {
+            "qty" => Ok(Self::Qty),
+            "filled" => Ok(Self::Filled),
+            unsupported => Err(IOError::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a supported subtract mode", unsupported),
+            )),
+        }
+    }
+}
+
+/// Given two WantedLists, find the righthand Items whose ItemID/Color isn't
+/// in the lefthand list. `subtract_inventories` silently ignores these, but
+/// when subtracting parts you own from parts you need they usually mean a
+/// mistake, like a wrong color. These are the Items `diff_inventories` reports
+/// as added.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to be subtracted from
+/// * `right_inventory`: WantedList to subtract
+///
+/// Example
+///
+/// ```
+/// use brickline::check_subtract;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color};
+///
+/// let needed = Item::build_test_item(ItemType::Part, ItemID::from("3039"), Some(Color(5)), None);
+/// let owned = Item::build_test_item(ItemType::Part, ItemID::from("3039"), Some(Color(11)), None);
+/// let left_inventory = WantedList { items: vec![needed] };
+/// let right_inventory = WantedList { items: vec![owned.clone()] };
+/// assert_eq!(check_subtract(&left_inventory, &right_inventory), vec![owned]);
+/// ```
+pub fn check_subtract(left_inventory: &WantedList, right_inventory: &WantedList) -> Vec<Item> {
+    diff_inventories(left_inventory, right_inventory).added
+}
+
+/// Given two WantedLists, take the MinQty of each righthand Item (e.g. a
+/// list of parts already purchased) off the lefthand Item with the same
+/// ItemID/Color. A missing MinQty counts as 1 on either side. Lefthand
+/// Items without a match are kept as they are, Items only in the righthand
+/// list are ignored, and the lefthand order and metadata are preserved.
+///
+/// Duplicate righthand entries are summed first, as in `restock_order`.
+/// The righthand quantity is only used up once: if the lefthand list
+/// repeats an ItemID/Color, the entries are covered in list order, each
+/// taking what it needs, with anything left over going to the last entry
+/// in `SubtractMode::Filled`.
+///
+/// # Arguments
+///
+/// * `left_inventory`: WantedList to subtract from
+/// * `right_inventory`: WantedList to subtract
+/// * `mode`: Whether to decrease MinQty or increase QtyFilled
+///
+/// Example
+///
+/// ```
+/// use brickline::{subtract_inventories, SubtractMode};
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty, QtyFilled};
+///
+/// let left_item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut right_item = left_item.clone();
+/// right_item.min_qty = Some(MinQty(5));
+///
+/// let left_inventory = WantedList { items: vec![left_item] };
+/// let right_inventory = WantedList { items: vec![right_item] };
+///
+/// let subtracted = subtract_inventories(&left_inventory, &right_inventory, SubtractMode::Qty);
+/// assert_eq!(subtracted.items[0].min_qty, Some(MinQty(15)));
+///
+/// let filled = subtract_inventories(&left_inventory, &right_inventory, SubtractMode::Filled);
+/// assert_eq!(filled.items[0].min_qty, Some(MinQty(20)));
+/// assert_eq!(filled.items[0].qty_filled, Some(QtyFilled(5)));
+/// ```
+pub fn subtract_inventories(
+    left_inventory: &WantedList,
+    right_inventory: &WantedList,
+    mode: SubtractMode,
+) -> WantedList {
+    let collapsed_right = collapse_duplicates(right_inventory);
+    // What's left of each righthand quantity, drawn down as lefthand Items
+    // use it
+    let mut right_qtys: BTreeMap<ItemColorHashKey<'_>, i32> = collapsed_right
+        .iter()
+        .map(|item| {
+            let qty = item.min_qty.as_ref().map_or(1, |qty| qty.0);
+            (JoinKey::IdColor.key_for(item), qty)
+        })
+        .collect();
+    let last_matches: BTreeMap<ItemColorHashKey<'_>, usize> = left_inventory
+        .iter()
+        .enumerate()
+        .map(|(position, item)| (JoinKey::IdColor.key_for(item), position))
+        .filter(|(key, _)| right_qtys.contains_key(key))
+        .collect();
+    left_inventory
+        .iter()
+        .enumerate()
+        .filter_map(|(position, left_item)| {
+            let key = JoinKey::IdColor.key_for(left_item);
+            let right_qty = match right_qtys.get_mut(&key) {
+                Some(right_qty) if *right_qty > 0 => right_qty,
+                _ => return Some(left_item.clone()),
+            };
+            let mut subtracted_item = left_item.clone();
+            match mode {
+                SubtractMode::Qty => {
+                    let left_qty = left_item.min_qty.as_ref().map_or(1, |qty| qty.0);
+                    let remaining_qty = left_qty.saturating_sub(*right_qty);
+                    *right_qty = right_qty.saturating_sub(left_qty.max(0));
+                    if remaining_qty <= 0 {
+                        return None;
+                    }
+                    subtracted_item.min_qty = Some(MinQty(remaining_qty));
+                }
+                SubtractMode::Filled => {
+                    let applied = if last_matches.get(&key) == Some(&position) {
+                        *right_qty
+                    } else {
+                        (*right_qty).min(left_item.remaining_qty())
+                    };
+                    *right_qty -= applied;
+                    let qty_filled = left_item.qty_filled.as_ref().map_or(0, |qty| qty.0);
+                    subtracted_item.qty_filled =
+                        Some(QtyFilled(qty_filled.saturating_add(applied)));
+                }
+            }
+            Some(subtracted_item)
+        })
+        .collect()
+}
+
+/// Given a target inventory and the current inventory, build the order
+/// needed to restock: each target Item's MinQty becomes the target quantity
+/// less the quantity in stock with the same ItemID/Color, and Items already
+/// stocked to (or past) their target are left out. A missing MinQty counts
+/// as 1, duplicate current entries are summed first, and the target's order
+/// and metadata are preserved.
+///
+/// # Arguments
+///
+/// * `target`: Inventory that should be in stock
+/// * `current`: Inventory actually in stock
+///
+/// Example
+///
+/// ```
+/// use brickline::restock_order;
+/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+///
+/// let target_item = Item::build_test_item(
+///       ItemType::Part,
+///       ItemID(String::from("3039")),
+///       Some(Color(5)),
+///       Some(MinQty(20)),
+/// );
+/// let mut current_item = target_item.clone();
+/// current_item.min_qty = Some(MinQty(12));
+///
+/// let target = WantedList { items: vec![target_item] };
+/// let current = WantedList { items: vec![current_item] };
+/// let order = restock_order(&target, &current);
+/// assert_eq!(order.items[0].min_qty, Some(MinQty(8)));
+/// ```
+pub fn restock_order(target: &WantedList, current: &WantedList) -> WantedList {
+    subtract_inventories(target, &collapse_duplicates(current), SubtractMode::Qty)
+}
+
+/// Given the arguments for the `join` command, join the two wanted lists,
+/// then write the result to the provided output path.
+///
+/// # Arguments
+///
+/// * `join_args`: Arguments to the join command
+///
+pub fn join(join_args: &ArgMatches) -> Result<(), BricklineError> {
+    let left_path = join_args.value_of("left").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty left inventory path",
+    ))?;
+    let right_path = join_args.value_of("right").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty right inventory path",
+    ))?;
+    let load_options = LoadOptions::from_args(join_args);
+    let (left_wanted_list, left_statistics) = load_wanted_list(left_path, &load_options)?;
+    let (right_wanted_list, right_statistics) = load_wanted_list(right_path, &load_options)?;
+    println!(
+        "Left Wanted list Statistics for {}\n{}\n",
+        left_path, left_statistics
+    );
+    println!(
+        "Right Wanted List Statistics for {}\n{}\n",
+        right_path, right_statistics
+    );
+    let join_options = JoinOptions::from_args(join_args)?;
+    let cap =
+        match join_args.value_of("cap") {
+            Some(cap) => Some(cap.parse::<u32>().ok().filter(|cap| *cap > 0).ok_or(
+                IOError::new(ErrorKind::InvalidInput, "cap must be a positive integer"),
+            )?),
+            None => None,
+        };
+    println!("Merging wanted lists...\n");
+    verbose!("Joining {} into {}", right_path, left_path);
+    let (mut joined_inventory, changes) =
+        join_inventories_tracked(&left_wanted_list, &right_wanted_list, &join_options);
+    println!(
+        "Items Incremented: {}, Items Added: {}\n",
+        changes.incremented.len(),
+        changes.added.len()
+    );
+    if let Some(exclude_path) = join_args.value_of("exclude") {
+        let exclude_list = std::fs::read_to_string(exclude_path)?.parse::<ExcludeList>()?;
+        joined_inventory.retain(|item| !exclude_list.excludes(item));
+    }
+    if let Some(changed_path) = join_args.value_of("changed-only") {
+        let changed = joined_inventory.filter(|item| {
+            changes
+                .incremented
+                .contains(&join_options.owned_key_for(item))
+        });
+        write_output(
+            &PathBuf::from(changed_path),
+            &changed.to_xml_indented(xml_indent(join_args)?)?,
+            "incremented items",
+            join_args,
+        )?;
+    }
+    if join_args.is_present("merge-colors") {
+        joined_inventory.merge_colors();
+    }
+    if let Some(cap) = cap {
+        joined_inventory.cap_min_qty(cap);
+    }
+    let joined_statistics = gen_statistics_with(&joined_inventory, join_options.default_qty);
+    println!("Merged Wanted List Statistics {}\n", joined_statistics);
+    if join_args.is_present("dry-run") {
+        println!(
+            "Dry run: {} items in, {} items out, {} incremented, {} added\n",
+            left_wanted_list.len() + right_wanted_list.len(),
+            joined_inventory.len(),
+            changes.incremented.len(),
+            changes.added.len()
+        );
+    }
+    if join_args.is_present("color-variants") {
+        for (item_id, colors) in joined_inventory.items_with_multiple_colors() {
+            let color_names: Vec<String> = colors.iter().map(format_color).collect();
+            println!(
+                "Note: {} appears in {} colors ({})",
+                item_id.0,
+                colors.len(),
+                color_names.join(", ")
+            );
+        }
+    }
+    let xml_string = joined_inventory.to_xml_indented(xml_indent(join_args)?)?;
+
+    let out_path_str = join_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "joined wanted list", join_args)?;
+    if join_args.is_present("summary") {
+        println!("\nJoined Wanted List Summary\n{}", joined_statistics);
+    }
+    Ok(())
+}
+
+/// Given the arguments for the `join-dir` command, join every `.xml` wanted
+/// list in the directory, in file name order, then write the result to the
+/// provided output path. Subdirectories aren't searched. With
+/// `--continue-on-error`, files that fail to load are reported and skipped.
+///
+/// # Arguments
+///
+/// * `join_dir_args`: Arguments to the join-dir command
+///
+pub fn join_dir(join_dir_args: &ArgMatches) -> Result<(), BricklineError> {
+    let dir_path = join_dir_args.value_of("directory").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty directory path",
+    ))?;
+    let load_options = LoadOptions::from_args(join_dir_args);
+    let directory_load = load_wanted_list_dir(Path::new(dir_path), &load_options)?;
+    for (xml_path, e) in directory_load.failed.iter() {
+        eprintln!("Skipped {}: {}", xml_path.display(), e);
+    }
+    if directory_load.loaded.is_empty() {
+        return Err(BricklineError::Io(IOError::new(
+            ErrorKind::InvalidData,
+            format!("None of the wanted lists in {} could be loaded", dir_path),
+        )));
+    }
+
+    let mut wanted_lists = Vec::new();
+    for (xml_path, wanted_list) in directory_load.loaded {
+        println!(
+            "Loaded {} ({} items)",
+            xml_path.display(),
+            wanted_list.len()
+        );
+        wanted_lists.push(wanted_list);
+    }
+    println!("\nMerging {} wanted lists...\n", wanted_lists.len());
+    let joined_inventory = join_many(&wanted_lists);
+    println!(
+        "Merged Wanted List Statistics {}\n",
+        gen_statistics(&joined_inventory)
+    );
+    let xml_string = joined_inventory.to_xml_indented(xml_indent(join_dir_args)?)?;
+
+    let out_path = output_base_path(join_dir_args)?;
+    write_output(&out_path, &xml_string, "joined wanted list", join_dir_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `merge` command, join every wanted list given
+/// with a repeated `-i`, in the order given, then write the result to the
+/// provided output path. At least two input lists are required.
+///
+/// # Arguments
+///
+/// * `merge_args`: Arguments to the merge command
+///
+pub fn merge(merge_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_paths: Vec<&str> = merge_args
+        .values_of("input")
+        .map(|paths| paths.collect())
+        .unwrap_or_default();
+    if input_paths.len() < 2 {
+        return Err(BricklineError::Io(IOError::new(
+            ErrorKind::InvalidInput,
+            "At least two input wanted lists are required",
+        )));
+    }
+    let load_options = LoadOptions::from_args(merge_args);
+    let mut wanted_lists = Vec::new();
+    for input_path in input_paths {
+        let (wanted_list, _) = load_wanted_list(input_path, &load_options)?;
+        println!("Loaded {} ({} items)", input_path, wanted_list.len());
+        wanted_lists.push((source_name(input_path), wanted_list));
+    }
+    println!("\nMerging {} wanted lists...\n", wanted_lists.len());
+    let merged_inventory = if merge_args.is_present("tag-source") {
+        join_many_tagged(&wanted_lists)
+    } else {
+        let wanted_lists: Vec<WantedList> = wanted_lists
+            .into_iter()
+            .map(|(_, wanted_list)| wanted_list)
+            .collect();
+        join_many(&wanted_lists)
+    };
+    println!(
+        "Merged Wanted List Statistics {}\n",
+        gen_statistics(&merged_inventory)
+    );
+    let xml_string = merged_inventory.to_xml_indented(xml_indent(merge_args)?)?;
+
+    let out_path_str = merge_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "merged wanted list", merge_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `diff` command, diff the two wanted lists.
+/// The report is written to the output path if one is provided, otherwise
+/// it is printed to stdout.
+///
+/// # Arguments
+///
+/// * `diff_args`: Arguments to the diff command
+///
+pub fn diff(diff_args: &ArgMatches) -> Result<(), BricklineError> {
+    let left_path = diff_args.value_of("left").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty left inventory path",
+    ))?;
+    let right_path = diff_args.value_of("right").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty right inventory path",
+    ))?;
+    let load_options = LoadOptions::from_args(diff_args);
+    let (left_wanted_list, _) = load_wanted_list(left_path, &load_options)?;
+    let (right_wanted_list, _) = load_wanted_list(right_path, &load_options)?;
+    let wanted_list_diff = diff_inventories(&left_wanted_list, &right_wanted_list);
+    let report = format!(
+        "Diff of {} against {}\n{}",
+        left_path, right_path, wanted_list_diff
+    );
+
+    match diff_args.value_of("output") {
+        Some(out_path_str) => {
+            let out_path = PathBuf::from(out_path_str);
+            write_output(&out_path, &report, "wanted list diff", diff_args)?;
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+/// Given the arguments for the `filter` command, select the matching Items
+/// out of the input wanted list, then write the result to the provided
+/// output path.
+///
+/// # Arguments
+///
+/// * `filter_args`: Arguments to the filter command
+///
+pub fn filter(filter_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = filter_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let item_filter = ItemFilter {
+        color: filter_args
+            .value_of("color")
+            .map(|c| c.parse::<i32>().map(Color))
+            .transpose()
+            .map_err(|e| IOError::new(ErrorKind::InvalidInput, e))?,
+        item_type: filter_args
+            .value_of("item-type")
+            .map(|t| ItemType::try_from(String::from(t)))
+            .transpose()?,
+        min_qty_gte: filter_args
+            .value_of("min-qty-gte")
+            .map(|q| q.parse::<i32>())
+            .transpose()
+            .map_err(|e| IOError::new(ErrorKind::InvalidInput, e))?,
+        max_price_lte: filter_args
+            .value_of("max-price-lte")
+            .map(|p| MaxPrice::try_from(String::from(p)))
+            .transpose()?,
+        include_unpriced: filter_args.is_present("include-unpriced"),
+    };
+
+    let (wanted_list, statistics) = file_to_wanted_list(input_path)?;
+    println!(
+        "Wanted List Statistics for {}\n{}\n",
+        input_path, statistics
+    );
+    let filtered = wanted_list.filter(|item| item_filter.matches(item));
+    let filtered_statistics = gen_statistics(&filtered);
+    println!("Filtered Wanted List Statistics {}\n", filtered_statistics);
+    let xml_string = filtered.to_xml_indented(xml_indent(filter_args)?)?;
+
+    let out_path_str = filter_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "filtered wanted list", filter_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `sort` command, sort the input wanted list
+/// into its canonical order, then write the result to the provided output
+/// path.
+///
+/// # Arguments
+///
+/// * `sort_args`: Arguments to the sort command
+///
+pub fn sort(sort_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = sort_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (mut wanted_list, _) = file_to_wanted_list(input_path)?;
+    match sort_args.value_of("sort-by") {
+        Some(key_str) => {
+            let key = key_str.parse::<SortKey>()?;
+            wanted_list.sort_by_field(key, sort_args.is_present("desc"));
+        }
+        None => wanted_list.sort(),
+    }
+    let xml_string = wanted_list.to_xml_indented(xml_indent(sort_args)?)?;
+
+    let out_path_str = sort_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "sorted wanted list", sort_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `normalize` command, rewrite the input wanted
+/// list into canonical form: duplicate ItemID/Color entries are collapsed
+/// (see `collapse_duplicates`), the Items are sorted into their canonical
+/// order, and the XML is always pretty-printed. Normalizing a normalized
+/// list gives back the same bytes, which keeps diffs in version control
+/// clean.
+///
+/// # Arguments
+///
+/// * `normalize_args`: Arguments to the normalize command
+///
+pub fn normalize(normalize_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = normalize_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    let mut normalized = collapse_duplicates(&wanted_list);
+    normalized.sort();
+    let xml_string =
+        normalized.to_xml_indented(Some(xml_indent(normalize_args)?.unwrap_or_default()))?;
+
+    let out_path_str = normalize_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(
+        &out_path,
+        &xml_string,
+        "normalized wanted list",
+        normalize_args,
+    )?;
+    Ok(())
+}
+
+/// Given the arguments for the `dedup` command, collapse the duplicate
+/// entries of the input wanted list, summing their MinQty, then write the
+/// result to the provided output path. `--key` picks which Item fields must
+/// match for entries to count as duplicates.
+///
+/// # Arguments
+///
+/// * `dedup_args`: Arguments to the dedup command
+///
+pub fn dedup(dedup_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = dedup_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let key = dedup_args
+        .value_of("key")
+        .unwrap_or("id-color")
+        .parse::<JoinKey>()?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    let deduped = collapse_duplicates_by(&wanted_list, key);
+    println!(
+        "Collapsed {} duplicate entries, {} remain",
+        wanted_list.len() - deduped.len(),
+        deduped.len()
+    );
+    let xml_string = deduped.to_xml_indented(xml_indent(dedup_args)?)?;
+
+    let out_path_str = dedup_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(
+        &out_path,
+        &xml_string,
+        "deduplicated wanted list",
+        dedup_args,
+    )?;
+    Ok(())
+}
+
+/// Given the arguments for the `top` command, print the `-n` Items of the
+/// input wanted list with the largest MinQty, largest first.
+///
+/// # Arguments
+///
+/// * `top_args`: Arguments to the top command
+///
+pub fn top(top_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = top_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let n = top_args
+        .value_of("count")
+        .unwrap_or("10")
+        .parse::<usize>()
+        .map_err(|e| IOError::new(ErrorKind::InvalidInput, e))?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    for item in wanted_list.top_by_qty(n) {
+        println!(
+            "{}: {}",
+            format_item_color(&item.item_id, &item.color),
+            format_min_qty(&item.min_qty)
+        );
+    }
+    Ok(())
+}
+
+/// Given the arguments for the `count` command, print the number of lots
+/// (Items) and total parts (summed MinQty, a missing MinQty counting as 1)
+/// in the input wanted list on one line, or just one of them for piping.
+///
+/// # Arguments
+///
+/// * `count_args`: Arguments to the count command
+///
+pub fn count(count_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = count_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (_, statistics) = file_to_wanted_list(input_path)?;
+    if count_args.is_present("lots-only") {
+        println!("{}", statistics.total_items);
+    } else if count_args.is_present("parts-only") {
+        println!("{}", statistics.total_parts);
+    } else {
+        println!("{} {}", statistics.total_items, statistics.total_parts);
+    }
+    Ok(())
+}
+
+/// Given the arguments for the `show` command, print the Item with the given
+/// ItemID and Color on one line, or every color of the ItemID if no color is
+/// given. It's an error if nothing matches.
 ///
 /// # Arguments
 ///
-/// * `message`: What message do you want to prompt the user with?
+/// * `show_args`: Arguments to the show command
 ///
-fn stdout_input(message: &str) -> Result<String, std::io::Error> {
-    print!("{}", message);
-    std::io::stdout().flush()?;
-    let mut buf = String::new();
-    std::io::stdin().read_line(&mut buf)?;
-    Ok(buf)
+pub fn show(show_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = show_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let item_id = show_args
+        .value_of("id")
+        .map(ItemID::from)
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty item ID"))?;
+    let color = show_args
+        .value_of("color")
+        .map(|c| c.parse::<i32>().map(Color))
+        .transpose()
+        .map_err(|e| IOError::new(ErrorKind::InvalidInput, e))?;
+
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    let matches: Vec<&Item> = match &color {
+        Some(color) => wanted_list.get(&item_id, Some(color)).into_iter().collect(),
+        None => wanted_list
+            .items
+            .iter()
+            .filter(|item| item.item_id == item_id)
+            .collect(),
+    };
+    if matches.is_empty() {
+        return Err(BricklineError::Io(IOError::new(
+            ErrorKind::NotFound,
+            format!(
+                "{} is not in {}",
+                format_item_color(&item_id, &color),
+                input_path
+            ),
+        )));
+    }
+    for item in matches {
+        println!("{}", item);
+    }
+    Ok(())
 }
 
-/// Write a file. If the file already exists, prompt the user to ask
-/// if they want to overwrite it.
+/// Given the arguments for the `stats` command, print the statistics of the
+/// input wanted list, either in their readable form or as JSON.
 ///
 /// # Arguments
 ///
-/// * `file_path`: Path to file to write
-/// * `content`: File content to write
+/// * `stats_args`: Arguments to the stats command
 ///
-fn write_file_with_overwrite_prompt(
-    file_path: &PathBuf,
-    content: &String,
-) -> Result<(), std::io::Error> {
-    if file_path.exists() {
-        let msg = format!(
-            "The file {} already exists. Do you want to overwrite this file? ",
-            file_path.to_str().unwrap()
-        );
-        let overwrite = stdout_input(&msg)?;
-        let lower = overwrite.to_lowercase();
-        let trimmed = lower.trim();
-        if trimmed != "y" && trimmed != "yes" {
-            println!("Exited without writing file");
-            std::process::exit(0x0100);
-        }
+pub fn stats(stats_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = stats_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let default_qty = stats_args
+        .value_of("default-qty")
+        .unwrap_or("one")
+        .parse::<DefaultQty>()?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    let statistics = gen_statistics_with(&wanted_list, default_qty);
+    if stats_args.is_present("json") {
+        let json = serde_json::to_string_pretty(&statistics)
+            .map_err(|e| IOError::new(ErrorKind::InvalidData, e))?;
+        println!("{}", json);
+    } else {
+        println!("Wanted List Statistics for {}\n{}", input_path, statistics);
     }
-
-    let mut file = File::create(file_path)?;
-    println!(
-        "Writing joined wanted list to {}",
-        file_path.to_str().unwrap()
-    );
-    file.write_all(content.as_bytes())?;
     Ok(())
 }
 
-/// Given a path to an XML file, load that file to a String
+/// Given the arguments for the `scale` command, multiply every MinQty in the
+/// input wanted list by the factor, then write the result to the provided
+/// output path.
 ///
 /// # Arguments
 ///
-/// * `file_path`: path to an XML file
+/// * `scale_args`: Arguments to the scale command
 ///
-/// # Example
+pub fn scale(scale_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = scale_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let factor = scale_args
+        .value_of("factor")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty factor"))?
+        .parse::<u32>()
+        .ok()
+        .filter(|factor| *factor > 0)
+        .ok_or(IOError::new(
+            ErrorKind::InvalidInput,
+            "factor must be a positive integer",
+        ))?;
+    let (mut wanted_list, _) = file_to_wanted_list(input_path)?;
+    wanted_list.scale(factor);
+    let xml_string = wanted_list.to_xml_indented(xml_indent(scale_args)?)?;
+
+    let out_path_str = scale_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "scaled wanted list", scale_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `remaining` command, rewrite the input wanted
+/// list to only what is still needed after the quantities already filled,
+/// then write the result to the provided output path.
 ///
-/// ```
-/// use std::path::PathBuf;
-/// use brickline::xml_to_string;
+/// # Arguments
 ///
-/// let path = PathBuf::from("/home/user/path/to/file.xml");
-/// let xml_string = xml_to_string(&path);
-/// ```
-pub fn xml_to_string(file_path: &PathBuf) -> Result<String, IOError> {
-    let mut file = File::open(file_path)?;
-    let mut xml_string = String::new();
-    file.read_to_string(&mut xml_string)?;
-    Ok(xml_string)
+/// * `remaining_args`: Arguments to the remaining command
+///
+pub fn remaining(remaining_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = remaining_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+    let xml_string = wanted_list
+        .remaining()
+        .to_xml_indented(xml_indent(remaining_args)?)?;
+
+    let out_path_str = remaining_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(
+        &out_path,
+        &xml_string,
+        "remaining wanted list",
+        remaining_args,
+    )?;
+    Ok(())
 }
 
-/// Given a path to a file, read the file and deserialize it to an WantedList
+/// Given the arguments for the `prune` command, drop the fully filled Items
+/// from the input wanted list (see `WantedList::prune_filled`), then write
+/// the result to the provided output path.
 ///
 /// # Arguments
 ///
-/// * `file_path`: String path to file
+/// * `prune_args`: Arguments to the prune command
 ///
-/// Example
+pub fn prune(prune_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = prune_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (mut wanted_list, _) = file_to_wanted_list(input_path)?;
+    let before = wanted_list.len();
+    wanted_list.prune_filled();
+    println!("Pruned {} filled items", before - wanted_list.len());
+    let xml_string = wanted_list.to_xml_indented(xml_indent(prune_args)?)?;
+
+    let out_path_str = prune_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "pruned wanted list", prune_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `set-condition` command, set the condition of
+/// every item in the input wanted list, or with `--only-missing` just the
+/// items that have none, then write the result to the provided output path.
 ///
-/// ```no_run
-/// use brickline::file_to_wanted_list;
+/// # Arguments
 ///
-/// let inventory = file_to_wanted_list("/path/to/wanted_list.xml");
-pub fn file_to_wanted_list(file_path: &str) -> Result<(WantedList, WantedListStatistics), IOError> {
-    let resource_path = PathBuf::from(file_path);
-    let resource_str = xml_to_string(&resource_path)?;
-    match from_str::<SerdeWantedList>(&resource_str) {
-        Ok(serde_inventory) => Ok(type_and_gen_statistics(serde_inventory)),
-        Err(e) => Err(IOError::new(ErrorKind::InvalidInput, e)),
+/// * `set_condition_args`: Arguments to the set-condition command
+///
+pub fn set_condition(set_condition_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = set_condition_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let condition = set_condition_args
+        .value_of("condition")
+        .map(|c| Condition::try_from(String::from(c)))
+        .transpose()?
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty condition"))?;
+    let (mut wanted_list, _) = file_to_wanted_list(input_path)?;
+    if set_condition_args.is_present("only-missing") {
+        wanted_list.set_condition_missing(condition);
+    } else {
+        wanted_list.set_condition_all(condition);
     }
+    let xml_string = wanted_list.to_xml_indented(xml_indent(set_condition_args)?)?;
+
+    let out_path_str = set_condition_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "wanted list", set_condition_args)?;
+    Ok(())
 }
 
-/// Given an WantedList, build a HashMap of each WantedList Item where
-/// the hash key is the ItemID and Color combination for the Item.
-/// Note: we explicitly .clone the Item for this map, as we're going to
-/// use it as the base case for our joined list.
+/// Work out the output path for a command that may write into a directory,
+/// from its `-o`, `--output-dir`, and `--prefix` arguments. `--output-dir`
+/// replaces the directory of the `-o` path and `--prefix` replaces its file
+/// stem, so `-o out.xml --output-dir lists --prefix batch` gives
+/// `lists/batch.xml`. Without `-o` the file is `wanted_list.xml`. A missing
+/// output directory is an error unless `--mkdir` is given, in which case it
+/// is created, except in a dry run.
 ///
 /// # Arguments
 ///
-/// * `inventory`: Bricklink inventory as deserialized from XML
+/// * `args`: Arguments to the command
 ///
-/// Example
+fn output_base_path(args: &ArgMatches) -> Result<PathBuf, IOError> {
+    let out_path = PathBuf::from(args.value_of("output").unwrap_or("wanted_list.xml"));
+    let out_path = match args.value_of("prefix") {
+        Some(prefix) => {
+            let extension = out_path.extension().map_or_else(
+                || String::from("xml"),
+                |ext| ext.to_string_lossy().into_owned(),
+            );
+            out_path.with_file_name(format!("{}.{}", prefix, extension))
+        }
+        None => out_path,
+    };
+    let out_dir = match args.value_of("output-dir") {
+        Some(out_dir) => PathBuf::from(out_dir),
+        None => return Ok(out_path),
+    };
+    if !out_dir.is_dir() {
+        if !args.is_present("mkdir") {
+            return Err(IOError::new(
+                ErrorKind::NotFound,
+                format!(
+                    "Output directory {} doesn't exist; pass --mkdir to create it",
+                    out_dir.display()
+                ),
+            ));
+        }
+        if !args.is_present("dry-run") {
+            std::fs::create_dir_all(&out_dir)?;
+        }
+    }
+    Ok(out_dir.join(out_path.file_name().unwrap_or_default()))
+}
+
+/// Build the path of the numbered output file for a chunk, so that
+/// `out.xml` becomes `out_1.xml`, `out_2.xml`, and so on.
 ///
-/// ```no_run
-/// use brickline::{xml_to_string, build_item_color_hashmap};
-/// use brickline::wanted::{WantedList, SerdeWantedList};
-/// use quick_xml::de::from_str;
-/// use std::path::PathBuf;
+/// # Arguments
 ///
-/// let path = PathBuf::from("/home/user/path/to/file.xml");
-/// let xml_string = xml_to_string(&path).unwrap();
-/// let wanted_list = WantedList::from(from_str::<SerdeWantedList>(&xml_string).unwrap());
-/// let hm = build_item_color_hashmap(&wanted_list);
-/// ```
-pub fn build_item_color_hashmap(inventory: &WantedList) -> BTreeMap<ItemColorHashKey, Item> {
-    inventory
-        .items
-        .iter()
-        .fold(BTreeMap::new(), |mut acc, item| {
-            let item_color_key = ItemColorHashKey {
-                item_id: &item.item_id,
-                color: &item.color,
-            };
-            // Cloning here as we're going to mutate these
-            // Items to combine them with other lists
-            acc.insert(item_color_key, item.clone());
-            acc
-        })
+/// * `base_path`: Output path given to the split command
+/// * `chunk_number`: 1-based number of the chunk
+///
+fn numbered_path(base_path: &Path, chunk_number: usize) -> PathBuf {
+    suffixed_path(base_path, &chunk_number.to_string())
 }
 
-/// Given two items, add the MinQty of the righthand (incrementing) Item to the
-/// lefthand (to-be-incremented) Item. The lefthand item_to_increment *will*
-/// be mutated.
+/// Build an output path with a suffix added to the file stem, so that
+/// `out.xml` with suffix `P` becomes `out_P.xml`.
 ///
 /// # Arguments
 ///
-/// * `item_to_increment`: Item to be incremented
-/// * `incrementing_item`: Item to increment from
+/// * `base_path`: Output path given to the command
+/// * `suffix`: Suffix to add after an underscore
 ///
-/// Example
+fn suffixed_path(base_path: &Path, suffix: &str) -> PathBuf {
+    let stem = base_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match base_path.extension() {
+        Some(extension) => format!("{}_{}.{}", stem, suffix, extension.to_string_lossy()),
+        None => format!("{}_{}", stem, suffix),
+    };
+    base_path.with_file_name(file_name)
+}
+
+/// Given the arguments for the `split` command, split the input wanted list
+/// into chunks of at most `max-items` Items, then write each chunk to a
+/// numbered file next to the provided output path.
 ///
-/// use brickline::increment_item;
-/// use brickline::wanted::Item;
+/// # Arguments
 ///
-/// let mut left_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3039")), Some(Color(5)), Some(MinQty(20)));
-/// let right_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3039")), Some(Color(5)), Some(MinQty(10)));
-
-/// increment_item(&mut left_item, &right_item);
+/// * `split_args`: Arguments to the split command
 ///
-fn increment_item(item_to_increment: &mut Item, incrementing_item: &Item) -> () {
-    let incrementing_min_qty = match &incrementing_item.min_qty {
-        Some(qty) => qty.0,
-        None => 1,
-    };
+pub fn split(split_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = split_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let max_items = split_args
+        .value_of("max-items")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty max items"))?
+        .parse::<usize>()
+        .ok()
+        .filter(|max_items| *max_items > 0)
+        .ok_or(IOError::new(
+            ErrorKind::InvalidInput,
+            "max-items must be a positive integer",
+        ))?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
 
-    match &item_to_increment.min_qty {
-        Some(qty) => item_to_increment.min_qty = Some(MinQty(qty.0 + incrementing_min_qty)),
-        None => item_to_increment.min_qty = Some(MinQty(1 + incrementing_min_qty)),
+    let out_path = output_base_path(split_args)?;
+    for (i, chunk) in wanted_list.chunk(max_items).into_iter().enumerate() {
+        let xml_string = chunk.to_xml_indented(xml_indent(split_args)?)?;
+        write_output(
+            &numbered_path(&out_path, i + 1),
+            &xml_string,
+            "wanted list chunk",
+            split_args,
+        )?;
     }
+    Ok(())
 }
 
-/// Given two Inventories, join the right inventory into the left one.
-/// Here's how the join happens:
-/// 1. Build hash table from left inventory
-/// 2. Iterate through right inventory and probe table for ItemId/Color keys
-/// 3. If a key is found, add the MinQty of the right inventory to the left.
-///    NOTE: The metadata from the *left* inventory is retained. There is no
-///    other metadata joining other than MinQty.
-/// 4. If no key is found, add the Item from the right inventory to the hash table
-/// 5. Convert the .values() of the hash table into .items of a new WantedList
+/// Given the arguments for the `split-by-type` command, split the input
+/// wanted list by ItemType, then write each type's Items to a file named
+/// after the provided output path and the type's code, e.g. `out_P.xml`.
+/// Types with no Items get no file.
 ///
 /// # Arguments
 ///
-/// * `left_inventory`: WantedList to be joined into
-/// * `right_inventory`: WantedList to join into left inventory
+/// * `split_args`: Arguments to the split-by-type command
 ///
-/// Example
+pub fn split_by_type(split_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = split_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let (wanted_list, _) = file_to_wanted_list(input_path)?;
+
+    let out_path = output_base_path(split_args)?;
+    for (item_type, partition) in wanted_list.partition_by_type() {
+        let xml_string = partition.to_xml_indented(xml_indent(split_args)?)?;
+        write_output(
+            &suffixed_path(&out_path, &String::from(item_type)),
+            &xml_string,
+            "wanted list partition",
+            split_args,
+        )?;
+    }
+    Ok(())
+}
+
+/// Given the arguments for the `validate` command, check the input wanted
+/// list for problems that would stop Bricklink from accepting it. Every
+/// problem found is printed, and an error is returned if there were any.
 ///
-/// ```
-/// use brickline::join_inventories;
-/// use brickline::wanted::{WantedList, Item, ItemID, ItemType, Color, MinQty};
+/// # Arguments
 ///
-/// let item = Item::build_test_item(
-///       ItemType::Part,
-///       ItemID(String::from("3039")),
-///       Some(Color(5)),
-///       Some(MinQty(20)),
-/// );
-/// let item_1 = item.clone();
+/// * `validate_args`: Arguments to the validate command
 ///
-/// let left_inventory = WantedList { items: vec![item] };
-/// let right_inventory = WantedList { items: vec![item_1] };
+pub fn validate(validate_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = validate_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let resource_str = xml_to_string(&PathBuf::from(input_path))?;
+    let serde_wanted_list = parse_wanted_list(&resource_str)?;
+
+    let validation_errors = wanted::validate(&serde_wanted_list);
+    if validation_errors.is_empty() {
+        println!("No problems found in {}", input_path);
+        return Ok(());
+    }
+    for validation_error in validation_errors.iter() {
+        println!("{}", validation_error);
+    }
+    Err(BricklineError::Validation(validation_errors))
+}
+
+/// Given the arguments for the `check-subtract` command, report each Item in
+/// the righthand wanted list whose ItemID/Color isn't in the lefthand one
+/// (see `check_subtract`). Finding any is an error, so scripts can stop
+/// before subtracting.
 ///
-/// let joined_inventory = join_inventories(&left_inventory, &right_inventory);
-/// ```
-pub fn join_inventories(left_inventory: &WantedList, right_inventory: &WantedList) -> WantedList {
-    let mut left_inv_map = build_item_color_hashmap(left_inventory);
-    right_inventory
-        .items
-        .iter()
-        .fold(&mut left_inv_map, |acc, right_item| {
-            let item_color_key = ItemColorHashKey {
-                item_id: &right_item.item_id,
-                color: &right_item.color,
-            };
-            if let Some(left_item) = acc.get_mut(&item_color_key) {
-                increment_item(left_item, right_item);
-            } else {
-                acc.insert(item_color_key, right_item.clone());
-            }
-            acc
-        });
-    WantedList {
-        items: left_inv_map.values().cloned().collect(),
+/// # Arguments
+///
+/// * `check_args`: Arguments to the check-subtract command
+///
+pub fn check_subtract_lists(check_args: &ArgMatches) -> Result<(), BricklineError> {
+    let left_path = check_args.value_of("left").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty left inventory path",
+    ))?;
+    let right_path = check_args.value_of("right").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty right inventory path",
+    ))?;
+    let (left_wanted_list, _) = file_to_wanted_list(left_path)?;
+    let (right_wanted_list, _) = file_to_wanted_list(right_path)?;
+    let unmatched = check_subtract(&left_wanted_list, &right_wanted_list);
+    if unmatched.is_empty() {
+        println!("Every item in {} is in {}", right_path, left_path);
+        return Ok(());
+    }
+    for item in unmatched.iter() {
+        println!(
+            "Not in {}: {}",
+            left_path,
+            format_item_color(&item.item_id, &item.color)
+        );
     }
+    Err(BricklineError::Io(IOError::new(
+        ErrorKind::InvalidInput,
+        format!(
+            "Found {} item(s) in {} that aren't in {}",
+            unmatched.len(),
+            right_path,
+            left_path
+        ),
+    )))
 }
 
-/// Given the arguments for the `join` command, join the two wanted lists,
-/// then write the result to the provided output path.
+/// Given the arguments for the `intersect` command, keep only the Items in
+/// both wanted lists, then write the result to the provided output path.
 ///
 /// # Arguments
 ///
-/// * `join_args`: Arguments to the join command
+/// * `intersect_args`: Arguments to the intersect command
 ///
-pub fn join(join_args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
-    let left_path = join_args.value_of("left").ok_or(IOError::new(
+pub fn intersect(intersect_args: &ArgMatches) -> Result<(), BricklineError> {
+    let left_path = intersect_args.value_of("left").ok_or(IOError::new(
         ErrorKind::InvalidInput,
         "Empty left inventory path",
     ))?;
-    let right_path = join_args.value_of("right").ok_or(IOError::new(
+    let right_path = intersect_args.value_of("right").ok_or(IOError::new(
         ErrorKind::InvalidInput,
         "Empty right inventory path",
     ))?;
-    let (left_wanted_list, left_statistics) = file_to_wanted_list(left_path)?;
-    let (right_wanted_list, right_statistics) = file_to_wanted_list(right_path)?;
+    let (left_wanted_list, _) = file_to_wanted_list(left_path)?;
+    let (right_wanted_list, _) = file_to_wanted_list(right_path)?;
+    let intersected = intersect_inventories(&left_wanted_list, &right_wanted_list);
+    let intersected_statistics = gen_statistics(&intersected);
     println!(
-        "Left Wanted list Statistics for {}\n{}\n",
-        left_path, left_statistics
+        "Intersected Wanted List Statistics {}\n",
+        intersected_statistics
+    );
+    let xml_string = intersected.to_xml_indented(xml_indent(intersect_args)?)?;
+
+    let out_path_str = intersect_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(
+        &out_path,
+        &xml_string,
+        "intersected wanted list",
+        intersect_args,
+    )?;
+    Ok(())
+}
+
+/// Given the arguments for the `subtract` command, take the righthand wanted
+/// list off the lefthand one, then write the result to the provided output
+/// path. `--mode` picks whether quantities come off MinQty or go on
+/// QtyFilled.
+///
+/// # Arguments
+///
+/// * `subtract_args`: Arguments to the subtract command
+///
+pub fn subtract(subtract_args: &ArgMatches) -> Result<(), BricklineError> {
+    let left_path = subtract_args.value_of("left").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty left inventory path",
+    ))?;
+    let right_path = subtract_args.value_of("right").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty right inventory path",
+    ))?;
+    let mode = subtract_args
+        .value_of("mode")
+        .unwrap_or("qty")
+        .parse::<SubtractMode>()?;
+    let (left_wanted_list, _) = file_to_wanted_list(left_path)?;
+    let (right_wanted_list, _) = file_to_wanted_list(right_path)?;
+    let subtracted = subtract_inventories(&left_wanted_list, &right_wanted_list, mode);
+    println!(
+        "Subtracted Wanted List Statistics {}\n",
+        gen_statistics(&subtracted)
     );
+    let xml_string = subtracted.to_xml_indented(xml_indent(subtract_args)?)?;
+
+    let out_path_str = subtract_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(
+        &out_path,
+        &xml_string,
+        "subtracted wanted list",
+        subtract_args,
+    )?;
+    Ok(())
+}
+
+/// Given the arguments for the `restock` command, build the restock order
+/// for the target inventory given the current one, then write it to the
+/// provided output path.
+///
+/// # Arguments
+///
+/// * `restock_args`: Arguments to the restock command
+///
+pub fn restock(restock_args: &ArgMatches) -> Result<(), BricklineError> {
+    let target_path = restock_args.value_of("target").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty target inventory path",
+    ))?;
+    let current_path = restock_args.value_of("current").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty current inventory path",
+    ))?;
+    let (target, _) = file_to_wanted_list(target_path)?;
+    let (current, _) = file_to_wanted_list(current_path)?;
+    let order = restock_order(&target, &current);
+    println!("Restock Order Statistics {}\n", gen_statistics(&order));
+    let xml_string = order.to_xml_indented(xml_indent(restock_args)?)?;
+
+    let out_path_str = restock_args
+        .value_of("output")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
+    let out_path = PathBuf::from(out_path_str);
+    write_output(&out_path, &xml_string, "restock order", restock_args)?;
+    Ok(())
+}
+
+/// Given the arguments for the `price` command, set the MaxPrice of the
+/// input wanted list's Items from a price guide CSV, optionally marked up,
+/// then write the result to the provided output path.
+///
+/// # Arguments
+///
+/// * `price_args`: Arguments to the price command
+///
+pub fn price(price_args: &ArgMatches) -> Result<(), BricklineError> {
+    let input_path = price_args
+        .value_of("input")
+        .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty input path"))?;
+    let guide_path = price_args.value_of("guide").ok_or(IOError::new(
+        ErrorKind::InvalidInput,
+        "Empty price guide path",
+    ))?;
+    let markup = price_args
+        .value_of("markup")
+        .unwrap_or("0")
+        .parse::<Markup>()?;
+    let round_to = match price_args.value_of("round") {
+        Some(round_str) => Some(
+            MaxPrice::parse_cents(round_str)
+                .filter(|cents| *cents > 0)
+                .map(MaxPrice)
+                .ok_or(IOError::new(
+                    ErrorKind::InvalidInput,
+                    "round must be a positive price increment, e.g. 0.05",
+                ))?,
+        ),
+        None => None,
+    };
+    let guide = std::fs::read_to_string(guide_path)?
+        .parse::<PriceGuide>()?
+        .adjusted(markup, round_to.as_ref());
+    let (mut wanted_list, _) = file_to_wanted_list(input_path)?;
+    let priced = apply_price_guide(&mut wanted_list, &guide);
     println!(
-        "Right Wanted List Statistics for {}\n{}\n",
-        right_path, right_statistics
+        "Priced {} of {} items from {}",
+        priced,
+        wanted_list.len(),
+        guide_path
     );
-    println!("Merging wanted lists...\n");
-    let joined_inventory = join_inventories(&left_wanted_list, &right_wanted_list);
-    let joined_statistics = gen_statistics(&joined_inventory);
-    println!("Merged Wanted List Statistics {}\n", joined_statistics);
-    let xml_string = String::try_from(joined_inventory)?;
+    let xml_string = wanted_list.to_xml_indented(xml_indent(price_args)?)?;
 
-    let out_path_str = join_args
+    let out_path_str = price_args
         .value_of("output")
         .ok_or(IOError::new(ErrorKind::InvalidInput, "Empty output path"))?;
     let out_path = PathBuf::from(out_path_str);
-    write_file_with_overwrite_prompt(&out_path, &xml_string)?;
+    write_output(&out_path, &xml_string, "priced wanted list", price_args)?;
     Ok(())
 }
 
@@ -309,12 +3427,14 @@ mod tests {
         let hm = build_item_color_hashmap(&inventory);
         assert_eq!(hm.len(), 3);
         let key_1 = ItemColorHashKey {
-            item_id: &ItemID(String::from("3622")),
+            item_id: Cow::Owned(ItemID(String::from("3622"))),
             color: &Some(Color(11)),
+            condition: None,
         };
         let key_2 = ItemColorHashKey {
-            item_id: &ItemID(String::from("3039")),
+            item_id: Cow::Owned(ItemID(String::from("3039"))),
             color: &None,
+            condition: None,
         };
         assert_eq!(hm.get(&key_1), Some(&item_1a));
         assert_eq!(hm.get(&key_2), Some(&item_2a));
@@ -335,7 +3455,7 @@ mod tests {
             Some(MinQty(10)),
         );
 
-        increment_item(&mut left_item, &right_item);
+        increment_item(&mut left_item, &right_item, DefaultQty::One);
         assert_eq!(left_item.min_qty.unwrap().0, 30);
     }
 
@@ -354,7 +3474,7 @@ mod tests {
             None,
         );
 
-        increment_item(&mut left_item, &right_item);
+        increment_item(&mut left_item, &right_item, DefaultQty::One);
         assert_eq!(left_item.min_qty.unwrap().0, 21);
     }
 
@@ -373,7 +3493,54 @@ mod tests {
             None,
         );
 
-        increment_item(&mut left_item, &right_item);
+        increment_item(&mut left_item, &right_item, DefaultQty::One);
         assert_eq!(left_item.min_qty.unwrap().0, 2);
     }
+
+    #[test]
+    fn test_write_file_assume_yes_overwrites() {
+        let mut file_path = std::env::temp_dir();
+        file_path.push("brickline_test_write_file_assume_yes.xml");
+        std::fs::write(&file_path, "original").unwrap();
+
+        // With assume_yes set we never prompt, so this doesn't block on stdin
+        write_file_with_overwrite_prompt(&file_path, &String::from("replaced"), "test", true)
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "replaced");
+
+        std::fs::remove_file(&file_path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_file_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut file_path = std::env::temp_dir();
+        file_path.push(OsStr::from_bytes(b"brickline_test_non_utf8_\xff.xml"));
+        std::fs::write(&file_path, "original").unwrap();
+
+        write_file_with_overwrite_prompt(&file_path, &String::from("replaced"), "test", true)
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "replaced");
+
+        std::fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_numbered_path() {
+        assert_eq!(
+            numbered_path(&PathBuf::from("lists/out.xml"), 2),
+            PathBuf::from("lists/out_2.xml")
+        );
+        assert_eq!(
+            numbered_path(&PathBuf::from("out"), 1),
+            PathBuf::from("out_1")
+        );
+        assert_eq!(
+            suffixed_path(&PathBuf::from("lists/out.xml"), "M"),
+            PathBuf::from("lists/out_M.xml")
+        );
+    }
 }
@@ -0,0 +1,36 @@
+//! Verbose progress logging
+//!
+//! Long running commands like a big merge are otherwise silent until they
+//! finish. When verbose logging is turned on, e.g. with the `--verbose`
+//! flag, the `verbose!` macro writes progress messages to stderr, keeping
+//! stdout clean for piping. It's off by default, so library users don't
+//! get any output they didn't ask for.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Turn verbose logging on or off for the whole process
+///
+/// # Arguments
+///
+/// * `verbose`: Whether `verbose!` messages should be written
+///
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Is verbose logging turned on?
+pub fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Write a progress message to stderr if verbose logging is turned on.
+/// Takes the same arguments as `eprintln!`.
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::logging::is_verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -2,20 +2,64 @@
 //!
 //! A small set of tools to manipulate Bricklink wanted lists and perform
 //! price analysis
-use std::error;
 use std::io::{Error as IOError, ErrorKind};
 
-use brickline::join;
+use brickline::error::BricklineError;
+use brickline::logging::set_verbose;
+use brickline::{
+    check_subtract_lists, count, dedup, diff, filter, intersect, join, join_dir, merge, normalize,
+    price, prune, remaining, restock, scale, set_condition, show, sort, split, split_by_type,
+    stats, subtract, top, validate,
+};
 
 use clap::{App, Arg};
 
-/// CLI Tooling
-
-fn main() -> Result<(), Box<dyn error::Error>> {
+/// CLI Tooling. Errors are printed with their Display message rather than
+/// their Debug representation, and exit with a non-zero status.
+fn main() {
     let commands = App::new("Bricktools")
         .version("0.1")
         .author("Rob Story")
         .about("Bricklink wanted list helper tools")
+        .arg(
+            Arg::with_name("yes")
+                .short('y')
+                .long("yes")
+                .global(true)
+                .about("Overwrite existing output files without prompting"),
+        )
+        .arg(
+            Arg::with_name("pretty")
+                .long("pretty")
+                .global(true)
+                .about("Write wanted lists as indented XML with one element per line"),
+        )
+        .arg(
+            Arg::with_name("indent")
+                .long("indent")
+                .global(true)
+                .takes_value(true)
+                .about("Indent pretty-printed wanted lists by this many spaces, or \"tab\" (default 2; implies --pretty)"),
+        )
+        .arg(
+            Arg::with_name("crlf")
+                .long("crlf")
+                .global(true)
+                .about("Write output files with CRLF line endings"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short('v')
+                .long("verbose")
+                .global(true)
+                .about("Log progress, like each file loaded and written, to stderr"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .global(true)
+                .about("Do everything but write output files, printing what would be written"),
+        )
         .subcommand(
             App::new("join")
                 .about("Merges two Bricklink wanted lists")
@@ -39,15 +83,814 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                         .required(true)
                         .takes_value(true)
                         .about("Path to joined output file"),
+                )
+                .arg(
+                    Arg::with_name("dedup")
+                        .long("dedup")
+                        .about("Collapse duplicate ItemID/Color entries within each list on load"),
+                )
+                .arg(
+                    Arg::with_name("validate")
+                        .long("validate")
+                        .about("Warn about color IDs not in the Bricklink color catalog on load"),
+                )
+                .arg(
+                    Arg::with_name("strategy")
+                        .long("strategy")
+                        .takes_value(true)
+                        .possible_values(&["sum", "max", "keep-left", "keep-right"])
+                        .default_value("sum")
+                        .about("How to combine the minimum quantities of matching items"),
+                )
+                .arg(
+                    Arg::with_name("missing-qty")
+                        .long("missing-qty")
+                        .takes_value(true)
+                        .possible_values(&["implicit-one", "adopt-right"])
+                        .default_value("implicit-one")
+                        .about("Whether a lefthand item with no minimum quantity counts as 1 or takes the righthand quantity"),
+                )
+                .arg(
+                    Arg::with_name("default-qty")
+                        .long("default-qty")
+                        .takes_value(true)
+                        .possible_values(&["one", "exclude"])
+                        .default_value("one")
+                        .about("Whether an item with no minimum quantity counts as 1 or as nothing when combining quantities"),
+                )
+                .arg(
+                    Arg::with_name("key")
+                        .long("key")
+                        .takes_value(true)
+                        .possible_values(&["id-color", "id-color-condition", "id"])
+                        .default_value("id-color")
+                        .about("Which item fields must match for items to be merged"),
+                )
+                .arg(
+                    Arg::with_name("order")
+                        .long("order")
+                        .takes_value(true)
+                        .possible_values(&["sorted", "preserve-left"])
+                        .default_value("sorted")
+                        .about("Sort the joined items, or keep the lefthand list's order"),
+                )
+                .arg(
+                    Arg::with_name("remarks")
+                        .long("remarks")
+                        .takes_value(true)
+                        .possible_values(&["keep-left", "keep-right", "concat"])
+                        .default_value("keep-left")
+                        .about("How to combine the remarks of matching items"),
+                )
+                .arg(
+                    Arg::with_name("price-policy")
+                        .long("price-policy")
+                        .takes_value(true)
+                        .possible_values(&["keep-left", "min", "max"])
+                        .default_value("keep-left")
+                        .about("How to combine the max prices of matching items"),
+                )
+                .arg(
+                    Arg::with_name("normalize-ids")
+                        .long("normalize-ids")
+                        .about("Merge items whose IDs only differ by a variant suffix, e.g. 3001 and 3001old"),
+                )
+                .arg(
+                    Arg::with_name("id-suffixes")
+                        .long("id-suffixes")
+                        .takes_value(true)
+                        .requires("normalize-ids")
+                        .about("Comma separated suffixes for --normalize-ids to strip, in order"),
+                )
+                .arg(
+                    Arg::with_name("summary")
+                        .long("summary")
+                        .about("Print the statistics of the joined list after writing it"),
+                )
+                .arg(
+                    Arg::with_name("color-variants")
+                        .long("color-variants")
+                        .about("Note any item IDs that appear in more than one color"),
+                )
+                .arg(
+                    Arg::with_name("exclude")
+                        .long("exclude")
+                        .takes_value(true)
+                        .about("Path to a file of item IDs, one per line and optionally followed by a color ID, to leave out of the joined list"),
+                )
+                .arg(
+                    Arg::with_name("changed-only")
+                        .long("changed-only")
+                        .takes_value(true)
+                        .about("Also write the items whose minimum quantity the join changed to this path"),
+                )
+                .arg(
+                    Arg::with_name("treat-nocolor-as-wildcard")
+                        .long("treat-nocolor-as-wildcard")
+                        .about("Join a colorless righthand item into every color of the same lefthand item ID"),
+                )
+                .arg(
+                    Arg::with_name("fill-missing")
+                        .long("fill-missing")
+                        .about("Fill in fields a left item is missing, like condition or remarks, from the matching right item"),
+                )
+                .arg(
+                    Arg::with_name("tag-source")
+                        .long("tag-source")
+                        .about("Append [from <right file name>] to the remarks of each item the right list adds or increments"),
+                )
+                .arg(
+                    Arg::with_name("cap")
+                        .long("cap")
+                        .takes_value(true)
+                        .about("Clamp each joined item's minimum quantity to at most this many"),
+                )
+                .arg(
+                    Arg::with_name("merge-colors")
+                        .long("merge-colors")
+                        .about("Collapse every color of an item ID into one colorless item"),
+                ),
+        )
+        .subcommand(
+            App::new("diff")
+                .about("Shows the differences between two Bricklink wanted lists")
+                .arg(
+                    Arg::with_name("left")
+                        .short('l')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the original wanted list"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .short('r')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to compare against the original"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .takes_value(true)
+                        .about("Optional path to write the diff report to, defaults to stdout"),
+                )
+                .arg(
+                    Arg::with_name("dedup")
+                        .long("dedup")
+                        .about("Collapse duplicate ItemID/Color entries within each list on load"),
+                )
+                .arg(
+                    Arg::with_name("validate")
+                        .long("validate")
+                        .about("Warn about color IDs not in the Bricklink color catalog on load"),
+                ),
+        )
+        .subcommand(
+            App::new("filter")
+                .about("Selects the items in a Bricklink wanted list matching every given filter")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to filter"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to filtered output file"),
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .about("Only keep items with this Bricklink color ID"),
+                )
+                .arg(
+                    Arg::with_name("item-type")
+                        .long("item-type")
+                        .takes_value(true)
+                        .possible_values(&["S", "P", "M", "B", "G", "C", "I", "O", "U"])
+                        .about("Only keep items of this item type"),
+                )
+                .arg(
+                    Arg::with_name("min-qty-gte")
+                        .long("min-qty-gte")
+                        .takes_value(true)
+                        .about("Only keep items with a minimum quantity of at least this much"),
+                )
+                .arg(
+                    Arg::with_name("max-price-lte")
+                        .long("max-price-lte")
+                        .takes_value(true)
+                        .about("Only keep items with a max price of at most this amount, e.g. 0.25"),
+                )
+                .arg(
+                    Arg::with_name("include-unpriced")
+                        .long("include-unpriced")
+                        .requires("max-price-lte")
+                        .about("Keep items with no max price when filtering with --max-price-lte"),
+                ),
+        )
+        .subcommand(
+            App::new("sort")
+                .about("Sorts a Bricklink wanted list by item type, item ID, then color")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to sort"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to sorted output file"),
+                )
+                .arg(
+                    Arg::with_name("sort-by")
+                        .long("sort-by")
+                        .takes_value(true)
+                        .possible_values(&["id", "qty", "color", "type", "price"])
+                        .about("Sort by this field instead, with ties ordered by item ID"),
+                )
+                .arg(
+                    Arg::with_name("desc")
+                        .long("desc")
+                        .requires("sort-by")
+                        .about("Sort the --sort-by field in descending order"),
+                ),
+        )
+        .subcommand(
+            App::new("normalize")
+                .about("Rewrites a Bricklink wanted list deduplicated, sorted, and pretty-printed")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to normalize"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to normalized output file"),
+                ),
+        )
+        .subcommand(
+            App::new("validate")
+                .about("Checks a Bricklink wanted list for problems before uploading it")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to validate"),
+                ),
+        )
+        .subcommand(
+            App::new("intersect")
+                .about("Keeps only the items found in both Bricklink wanted lists")
+                .arg(
+                    Arg::with_name("left")
+                        .short('l')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to lefthand wanted list, its metadata is kept"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .short('r')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to righthand wanted list"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to intersected output file"),
+                ),
+        )
+        .subcommand(
+            App::new("subtract")
+                .about("Takes the items of one Bricklink wanted list off another")
+                .arg(
+                    Arg::with_name("left")
+                        .short('l')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to subtract from"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .short('r')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to subtract, e.g. parts already bought"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to subtracted output file"),
+                )
+                .arg(
+                    Arg::with_name("mode")
+                        .long("mode")
+                        .takes_value(true)
+                        .possible_values(&["qty", "filled"])
+                        .default_value("qty")
+                        .about("Decrease minimum quantities, or increase quantities filled instead"),
+                ),
+        )
+        .subcommand(
+            App::new("check-subtract")
+                .about("Checks that every item of a Bricklink wanted list to subtract is in the list to subtract from")
+                .arg(
+                    Arg::with_name("left")
+                        .short('l')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to subtract from"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .short('r')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to subtract, e.g. parts already bought"),
+                ),
+        )
+        .subcommand(
+            App::new("price")
+                .about("Sets the max prices of a Bricklink wanted list from a price guide CSV")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to price"),
+                )
+                .arg(
+                    Arg::with_name("guide")
+                        .short('g')
+                        .long("guide")
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to a price guide CSV of item ID, color ID, and average price"),
+                )
+                .arg(
+                    Arg::with_name("markup")
+                        .long("markup")
+                        .takes_value(true)
+                        .default_value("0")
+                        .about("Percentage to mark each guide price up by, e.g. 10"),
+                )
+                .arg(
+                    Arg::with_name("round")
+                        .long("round")
+                        .takes_value(true)
+                        .about("Round each marked up price half-up to a multiple of this increment, e.g. 0.05"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to priced output file"),
+                ),
+        )
+        .subcommand(
+            App::new("restock")
+                .about("Builds the order needed to bring a current inventory up to a target")
+                .arg(
+                    Arg::with_name("target")
+                        .long("target")
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the inventory that should be in stock"),
+                )
+                .arg(
+                    Arg::with_name("current")
+                        .long("current")
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the inventory currently in stock"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to restock order output file"),
+                ),
+        )
+        .subcommand(
+            App::new("join-dir")
+                .about("Joins every Bricklink wanted list in a directory")
+                .arg(
+                    Arg::with_name("directory")
+                        .short('d')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Directory of .xml wanted lists to join"),
+                )
+                .arg(
+                    Arg::with_name("continue-on-error")
+                        .long("continue-on-error")
+                        .about("Skip wanted lists that fail to load instead of stopping"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required_unless_one(&["output-dir", "prefix"])
+                        .takes_value(true)
+                        .about("Path to joined output file"),
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .takes_value(true)
+                        .about("Directory to write the output file, in place of the -o directory"),
+                )
+                .arg(
+                    Arg::with_name("prefix")
+                        .long("prefix")
+                        .takes_value(true)
+                        .about("File name to start the output file with, in place of the -o file name"),
+                )
+                .arg(
+                    Arg::with_name("mkdir")
+                        .long("mkdir")
+                        .about("Create the output directory if it doesn't exist"),
+                ),
+        )
+        .subcommand(
+            App::new("merge")
+                .about("Joins any number of Bricklink wanted lists given with a repeated -i")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .about("Path to a wanted list to merge, given at least twice"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to merged output file"),
+                )
+                .arg(
+                    Arg::with_name("dedup")
+                        .long("dedup")
+                        .about("Collapse duplicate ItemID/Color entries within each list on load"),
+                )
+                .arg(
+                    Arg::with_name("tag-source")
+                        .long("tag-source")
+                        .about("Append [from <file name>] to the remarks of each item a list adds or increments"),
+                ),
+        )
+        .subcommand(
+            App::new("top")
+                .about("Lists the items of a Bricklink wanted list with the largest minimum quantities")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .short('n')
+                        .takes_value(true)
+                        .default_value("10")
+                        .about("How many items to list"),
+                ),
+        )
+        .subcommand(
+            App::new("stats")
+                .about("Prints the statistics of a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .about("Print the statistics as JSON"),
+                )
+                .arg(
+                    Arg::with_name("default-qty")
+                        .long("default-qty")
+                        .takes_value(true)
+                        .possible_values(&["one", "exclude"])
+                        .default_value("one")
+                        .about("Whether an item with no minimum quantity counts as 1 or is left out of part sums"),
+                ),
+        )
+        .subcommand(
+            App::new("dedup")
+                .about("Collapses duplicate entries in a Bricklink wanted list, summing quantities")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to deduplicate"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to deduplicated output file"),
+                )
+                .arg(
+                    Arg::with_name("key")
+                        .long("key")
+                        .takes_value(true)
+                        .possible_values(&["id-color", "id-color-condition", "id"])
+                        .default_value("id-color")
+                        .about("Which item fields must match for entries to be duplicates"),
+                ),
+        )
+        .subcommand(
+            App::new("count")
+                .about("Prints the number of lots and total parts in a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("lots-only")
+                        .long("lots-only")
+                        .conflicts_with("parts-only")
+                        .about("Print only the number of lots"),
+                )
+                .arg(
+                    Arg::with_name("parts-only")
+                        .long("parts-only")
+                        .about("Print only the total number of parts"),
+                ),
+        )
+        .subcommand(
+            App::new("show")
+                .about("Prints the details of one item of a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("id")
+                        .long("id")
+                        .required(true)
+                        .takes_value(true)
+                        .about("ItemID of the item to show"),
+                )
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .about("Color ID of the item to show. Every color of the item is shown if omitted"),
+                ),
+        )
+        .subcommand(
+            App::new("scale")
+                .about("Multiplies every minimum quantity in a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to scale"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to scaled output file"),
+                )
+                .arg(
+                    Arg::with_name("factor")
+                        .long("factor")
+                        .required(true)
+                        .takes_value(true)
+                        .about("Positive integer to multiply each minimum quantity by"),
+                ),
+        )
+        .subcommand(
+            App::new("remaining")
+                .about("Rewrites a Bricklink wanted list to what is still needed after filled quantities")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to remaining output file"),
+                ),
+        )
+        .subcommand(
+            App::new("prune")
+                .about("Drops the fully filled items from a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to pruned output file"),
+                ),
+        )
+        .subcommand(
+            App::new("set-condition")
+                .about("Sets the condition of every item in a Bricklink wanted list")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to output file"),
+                )
+                .arg(
+                    Arg::with_name("condition")
+                        .long("condition")
+                        .required(true)
+                        .takes_value(true)
+                        .possible_values(&["N", "U", "C", "I", "S", "X"])
+                        .about("Condition to set"),
+                )
+                .arg(
+                    Arg::with_name("only-missing")
+                        .long("only-missing")
+                        .about("Only set the condition of items that have none"),
+                ),
+        )
+        .subcommand(
+            App::new("split")
+                .about("Splits a Bricklink wanted list into smaller lists for staged uploads")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to split"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required_unless_one(&["output-dir", "prefix"])
+                        .takes_value(true)
+                        .about(
+                            "Base path for the output files, numbered out_1.xml, out_2.xml, ...",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .takes_value(true)
+                        .about("Directory to write the output files, in place of the -o directory"),
+                )
+                .arg(
+                    Arg::with_name("prefix")
+                        .long("prefix")
+                        .takes_value(true)
+                        .about("File name to start the output files with, in place of the -o file name"),
+                )
+                .arg(
+                    Arg::with_name("mkdir")
+                        .long("mkdir")
+                        .about("Create the output directory if it doesn't exist"),
+                )
+                .arg(
+                    Arg::with_name("max-items")
+                        .short('n')
+                        .long("max-items")
+                        .required(true)
+                        .takes_value(true)
+                        .about("Maximum number of items in each output file"),
+                ),
+        )
+        .subcommand(
+            App::new("split-by-type")
+                .about("Splits a Bricklink wanted list into one list per item type")
+                .arg(
+                    Arg::with_name("input")
+                        .short('i')
+                        .required(true)
+                        .takes_value(true)
+                        .about("Path to the wanted list to split"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short('o')
+                        .required_unless_one(&["output-dir", "prefix"])
+                        .takes_value(true)
+                        .about("Base path for the output files, named by item type like out_P.xml"),
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .takes_value(true)
+                        .about("Directory to write the output files, in place of the -o directory"),
+                )
+                .arg(
+                    Arg::with_name("prefix")
+                        .long("prefix")
+                        .takes_value(true)
+                        .about("File name to start the output files with, in place of the -o file name"),
+                )
+                .arg(
+                    Arg::with_name("mkdir")
+                        .long("mkdir")
+                        .about("Create the output directory if it doesn't exist"),
                 ),
         )
         .get_matches();
 
-    match commands.subcommand() {
+    // Global flags are propagated down to the subcommand's matches
+    let verbose = match commands.subcommand() {
+        (_, Some(subcommand_args)) => subcommand_args.is_present("verbose"),
+        _ => commands.is_present("verbose"),
+    };
+    set_verbose(verbose);
+
+    let result = match commands.subcommand() {
         ("join", Some(join_args)) => join(join_args),
-        _ => Err(Box::new(IOError::new(
+        ("diff", Some(diff_args)) => diff(diff_args),
+        ("filter", Some(filter_args)) => filter(filter_args),
+        ("sort", Some(sort_args)) => sort(sort_args),
+        ("normalize", Some(normalize_args)) => normalize(normalize_args),
+        ("validate", Some(validate_args)) => validate(validate_args),
+        ("intersect", Some(intersect_args)) => intersect(intersect_args),
+        ("subtract", Some(subtract_args)) => subtract(subtract_args),
+        ("check-subtract", Some(check_args)) => check_subtract_lists(check_args),
+        ("restock", Some(restock_args)) => restock(restock_args),
+        ("price", Some(price_args)) => price(price_args),
+        ("join-dir", Some(join_dir_args)) => join_dir(join_dir_args),
+        ("merge", Some(merge_args)) => merge(merge_args),
+        ("top", Some(top_args)) => top(top_args),
+        ("stats", Some(stats_args)) => stats(stats_args),
+        ("count", Some(count_args)) => count(count_args),
+        ("dedup", Some(dedup_args)) => dedup(dedup_args),
+        ("show", Some(show_args)) => show(show_args),
+        ("scale", Some(scale_args)) => scale(scale_args),
+        ("remaining", Some(remaining_args)) => remaining(remaining_args),
+        ("prune", Some(prune_args)) => prune(prune_args),
+        ("set-condition", Some(set_condition_args)) => set_condition(set_condition_args),
+        ("split", Some(split_args)) => split(split_args),
+        ("split-by-type", Some(split_args)) => split_by_type(split_args),
+        _ => Err(BricklineError::Io(IOError::new(
             ErrorKind::InvalidInput,
             "Invalid command input",
         ))),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
     }
 }
@@ -11,37 +11,945 @@
 //! types to more complex ones. It's a bummer, but I don't expect to ever have Bricklink
 //! wanted lists longer than O(thousands) of Items, so I'm willing to take perf hit
 //! to do the full scan for deserialization/serialization.
+//!
+//! The single letter codes (ItemType, Condition, Notify, WantedShow) are trimmed
+//! and uppercased before matching, since hand-edited wanted lists aren't always
+//! tidy. They're always serialized back out in their canonical form.
+//!
+//! Exported lists sometimes carry list-level metadata (attributes on INVENTORY,
+//! or elements like BASECOMMAND alongside the ITEMs) that isn't part of the
+//! schema. That's ignored on the way in and not written back out. Unknown
+//! elements inside an ITEM are different: they're kept, and written back out
+//! after the fields we know about.
+use crate::colors::{BRICKLINK_COLORS, BRICKLINK_COLOR_IDS};
+
+use quick_xml::events::Event;
 use quick_xml::se::to_string;
-use quick_xml::DeError;
+use quick_xml::{DeError, Reader, Writer};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::convert::TryFrom;
+
+/// A deserialized field value that couldn't be converted to its typed
+/// representation, e.g. a <MAXPRICE> that isn't a number
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub field: &'static str,
+    pub value: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Could not parse {} value \"{}\"", self.field, self.value)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The serde wanted_list of SerdeItems
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename(serialize = "INVENTORY"))]
+pub struct SerdeWantedList {
+    #[serde(rename(serialize = "ITEM", deserialize = "$value"))]
+    #[serde(default, deserialize_with = "deserialize_known_items")]
+    pub items: Vec<SerdeItem>,
+}
+
+/// A child element of the INVENTORY element. Anything other than an ITEM
+/// is list-level metadata that we don't model.
+#[derive(Deserialize)]
+enum InventoryElement {
+    #[serde(rename = "ITEM")]
+    Item(Box<SerdeItem>),
+    #[serde(other)]
+    Unknown,
+}
+
+/// Deserialize the children of the INVENTORY element, keeping the ITEMs
+/// and skipping any other elements wherever they appear.
+fn deserialize_known_items<'de, D>(deserializer: D) -> Result<Vec<SerdeItem>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let elements = Vec::<InventoryElement>::deserialize(deserializer)?;
+    Ok(elements
+        .into_iter()
+        .filter_map(|element| match element {
+            InventoryElement::Item(item) => Some(*item),
+            InventoryElement::Unknown => None,
+        })
+        .collect())
+}
+
+impl SerdeWantedList {
+    /// Dirty fix for a serialization issue with the quick_xml library.
+    /// When we try to serialize a Vec<SerdeItem>, we end up with
+    /// <ITEM><ITEM>...</ITEM></ITEM> at the beginning and end of the
+    /// vectors. So...we're going to straight up remove the redundant
+    /// Items by locating those tags in the String. Matching on the tags
+    /// rather than on byte offsets keeps this safe for multi-byte
+    /// characters in the Item fields. If the tags aren't where we expect
+    /// them, the String is left as it is apart from the XML declaration.
+    ///
+    ///
+    /// # Arguments
+    ///
+    /// * `serde_string`: Serialized String of a SerdeWantedList
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quick_xml::se::to_string;
+    /// use brickline::wanted::{WantedList, SerdeWantedList, Item, ItemType,
+    ///                         ItemID, Color};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     None
+    /// );
+    /// let wanted_list = WantedList { items: vec![test_item]};
+    /// let serde_wanted_list = SerdeWantedList::from(wanted_list);
+    /// let stringified = to_string(&serde_wanted_list).unwrap();
+    /// let repaired = SerdeWantedList::amend_serialized_string(stringified);
+    /// ```
+    pub fn amend_serialized_string(serde_string: String) -> String {
+        let declaration = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+        // The unknown elements of an Item are serialized inside a wrapper
+        // element, which is dropped so they sit directly in the ITEM again
+        let serde_string = serde_string
+            .replace("<BRICKLINE_EXTRA>", "")
+            .replace("</BRICKLINE_EXTRA>", "");
+        let items = serde_string
+            .strip_prefix("<INVENTORY><ITEM>")
+            .and_then(|rest| rest.strip_suffix("</ITEM></INVENTORY>"));
+        match items {
+            Some(items) => format!("{}<INVENTORY>{}</INVENTORY>", declaration, items),
+            None => format!("{}{}", declaration, serde_string),
+        }
+    }
+}
+
+/// A Bricklink WantedList
+#[derive(Clone, Debug, PartialEq)]
+pub struct WantedList {
+    pub items: Vec<Item>,
+}
+
+impl WantedList {
+    /// The number of Items in the WantedList
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID};
+    ///
+    /// let test_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, None);
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// assert_eq!(wanted_list.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Does the WantedList have no Items?
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterate over the Items in list order
+    pub fn iter(&self) -> std::slice::Iter<'_, Item> {
+        self.items.iter()
+    }
+
+    /// Join another WantedList into this one in place, keeping the order of
+    /// both: an Item whose ItemID/Color is already in this list has its MinQty
+    /// added to the first Item with that key, as `join_inventories` does, and
+    /// any other Item is moved to the end. This is the owned, order-preserving
+    /// counterpart to `join_inventories`; nothing is cloned, and Items already
+    /// in this list are never dropped or reordered, even duplicates.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: WantedList to join into this one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, MinQty};
+    ///
+    /// let item = Item::build_test_item(ItemType::Part, ItemID::from("3622"), None, Some(MinQty(2)));
+    /// let mut wanted_list = WantedList { items: vec![item.clone()] };
+    /// wanted_list.append(WantedList { items: vec![item] });
+    /// assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(4)));
+    /// ```
+    pub fn append(&mut self, other: WantedList) {
+        let mut positions: BTreeMap<(ItemID, Option<Color>), usize> = BTreeMap::new();
+        for (position, item) in self.items.iter().enumerate() {
+            positions.entry(item.color_key()).or_insert(position);
+        }
+        for item in other.items {
+            let item_color_key = item.color_key();
+            match positions.get(&item_color_key) {
+                Some(&position) => {
+                    crate::increment_item(&mut self.items[position], &item, DefaultQty::One)
+                }
+                None => {
+                    positions.insert(item_color_key, self.items.len());
+                    self.items.push(item);
+                }
+            }
+        }
+    }
+
+    /// Keep only the Items that match the predicate, in place. Unlike
+    /// `filter`, no new WantedList is allocated. Item order is preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred`: Returns true for Items that should be kept
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     None
+    /// );
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.retain(|item| item.color == Some(Color(5)));
+    /// assert!(wanted_list.is_empty());
+    /// ```
+    pub fn retain<F: FnMut(&Item) -> bool>(&mut self, pred: F) {
+        self.items.retain(pred)
+    }
+
+    /// Look up the first Item with the given ItemID and Color, where a Color
+    /// of None only matches colorless Items. Each call scans the list, so
+    /// for many lookups against the same list it's worth building an index
+    /// once with `brickline::build_item_color_hashmap` instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id`: ItemID to look for
+    /// * `color`: Color to look for, or None for a colorless Item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     Some(MinQty(4))
+    /// );
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let item = wanted_list.get(&ItemID(String::from("3622")), Some(&Color(11)));
+    /// assert_eq!(item.and_then(|item| item.min_qty.clone()), Some(MinQty(4)));
+    /// ```
+    pub fn get(&self, item_id: &ItemID, color: Option<&Color>) -> Option<&Item> {
+        self.iter()
+            .find(|item| &item.item_id == item_id && item.color.as_ref() == color)
+    }
+
+    /// Does the WantedList have an Item with the given ItemID and Color?
+    /// See `get`.
+    ///
+    /// # Arguments
+    ///
+    /// * `item_id`: ItemID to look for
+    /// * `color`: Color to look for, or None for a colorless Item
+    ///
+    pub fn contains(&self, item_id: &ItemID, color: Option<&Color>) -> bool {
+        self.get(item_id, color).is_some()
+    }
+
+    /// Build a new WantedList containing clones of only the Items that
+    /// match the predicate. Item order is preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `pred`: Returns true for Items that should be kept
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     None
+    /// );
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let filtered = wanted_list.filter(|item| item.color == Some(Color(5)));
+    /// assert!(filtered.items.is_empty());
+    /// ```
+    pub fn filter<F: Fn(&Item) -> bool>(&self, pred: F) -> WantedList {
+        self.iter().filter(|item| pred(item)).cloned().collect()
+    }
+
+    /// Consume the WantedList and build a new one by applying `f` to every
+    /// Item, preserving Item order. Useful for uniform transformations like
+    /// bumping every quantity or stripping every remark.
+    ///
+    /// # Arguments
+    ///
+    /// * `f`: Transforms each Item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, MinQty};
+    ///
+    /// let test_item = Item::build_test_item(ItemType::Part, ItemID::from("3622"), None, Some(MinQty(4)));
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let doubled = wanted_list.map_items(|mut item| {
+    ///     item.min_qty = item.min_qty.map(|min_qty| MinQty(min_qty.0 * 2));
+    ///     item
+    /// });
+    /// assert_eq!(doubled.items[0].min_qty, Some(MinQty(8)));
+    /// ```
+    pub fn map_items<F: FnMut(Item) -> Item>(self, f: F) -> WantedList {
+        self.items.into_iter().map(f).collect()
+    }
+
+    /// Split the WantedList into WantedLists of at most `max_items` Items
+    /// each, for uploading a large list in stages. Item order and metadata
+    /// are preserved, and the last chunk may be smaller.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_items`: Maximum number of Items in each chunk. Panics if 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     None
+    /// );
+    /// let wanted_list = WantedList { items: vec![test_item.clone(), test_item] };
+    /// let chunks = wanted_list.chunk(1);
+    /// assert_eq!(chunks.len(), 2);
+    /// ```
+    pub fn chunk(&self, max_items: usize) -> Vec<WantedList> {
+        self.items
+            .chunks(max_items)
+            .map(|items| WantedList {
+                items: items.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Split the WantedList into one WantedList per ItemType, e.g. for
+    /// buying parts and minifigs from different sellers. Item order and
+    /// metadata are preserved within each partition, and types with no
+    /// Items have no entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let part = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     None
+    /// );
+    /// let minifig = Item::build_test_item(
+    ///     ItemType::Minifig,
+    ///     ItemID(String::from("sw0001")),
+    ///     None,
+    ///     None
+    /// );
+    /// let wanted_list = WantedList { items: vec![part, minifig] };
+    /// let partitions = wanted_list.partition_by_type();
+    /// assert_eq!(partitions.len(), 2);
+    /// assert_eq!(partitions[&ItemType::Minifig].len(), 1);
+    /// ```
+    pub fn partition_by_type(&self) -> BTreeMap<ItemType, WantedList> {
+        self.group_by(|item| item.item_type.clone())
+            .into_iter()
+            .map(|(item_type, items)| {
+                let items = items.into_iter().cloned().collect();
+                (item_type, WantedList { items })
+            })
+            .collect()
+    }
+
+    /// Group the Items by a key computed from each one, e.g. their ItemType
+    /// or the first digit of their ItemID. The groups are in key order, and
+    /// each keeps its Items in list order.
+    ///
+    /// # Arguments
+    ///
+    /// * `f`: Computes the key to group an Item under
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID};
+    ///
+    /// let item_1 = Item::build_test_item(ItemType::Part, ItemID::from("3001"), None, None);
+    /// let item_2 = Item::build_test_item(ItemType::Part, ItemID::from("3622"), None, None);
+    /// let item_3 = Item::build_test_item(ItemType::Part, ItemID::from("4070"), None, None);
+    /// let wanted_list = WantedList { items: vec![item_1, item_2, item_3] };
+    /// let groups = wanted_list.group_by(|item| item.item_id.0.chars().next());
+    /// assert_eq!(groups[&Some('3')].len(), 2);
+    /// ```
+    pub fn group_by<K: Ord, F: Fn(&Item) -> K>(&self, f: F) -> BTreeMap<K, Vec<&Item>> {
+        let mut groups: BTreeMap<K, Vec<&Item>> = BTreeMap::new();
+        for item in self {
+            groups.entry(f(item)).or_default().push(item);
+        }
+        groups
+    }
+
+    /// The `n` Items with the largest MinQty, largest first. A missing
+    /// MinQty counts as 1. Ties are broken by ItemID, then Color, so the
+    /// result doesn't depend on the order of the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `n`: How many Items to return, at most
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let few = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), Some(MinQty(2)));
+    /// let many = Item::build_test_item(ItemType::Part, ItemID(String::from("3002")), Some(Color(5)), Some(MinQty(20)));
+    /// let wanted_list = WantedList { items: vec![few, many] };
+    /// let top = wanted_list.top_by_qty(1);
+    /// assert_eq!(top[0].item_id, ItemID(String::from("3002")));
+    /// ```
+    pub fn top_by_qty(&self, n: usize) -> Vec<&Item> {
+        let min_qty = |item: &Item| item.min_qty.as_ref().map_or(1, |min_qty| min_qty.0);
+        let mut items: Vec<&Item> = self.items.iter().collect();
+        items.sort_by(|a, b| {
+            min_qty(b)
+                .cmp(&min_qty(a))
+                .then_with(|| a.item_id.cmp(&b.item_id))
+                .then_with(|| a.color.cmp(&b.color))
+        });
+        items.truncate(n);
+        items
+    }
+
+    /// Find the ItemIDs that appear in more than one Color, which often
+    /// signals a catalog mismatch. Each ItemID maps to its Colors in
+    /// ascending order without repeats; Items without a Color are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let red = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), None);
+    /// let black = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(11)), None);
+    /// let wanted_list = WantedList { items: vec![red, black] };
+    /// let multiple_colors = wanted_list.items_with_multiple_colors();
+    /// assert_eq!(multiple_colors[&ItemID(String::from("3001"))], vec![Color(5), Color(11)]);
+    /// ```
+    pub fn items_with_multiple_colors(&self) -> BTreeMap<ItemID, Vec<Color>> {
+        let mut colors_by_item: BTreeMap<ItemID, BTreeSet<Color>> = BTreeMap::new();
+        for item in self.items.iter() {
+            if let Some(color) = &item.color {
+                colors_by_item
+                    .entry(item.item_id.clone())
+                    .or_default()
+                    .insert(color.clone());
+            }
+        }
+        colors_by_item
+            .into_iter()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(item_id, colors)| (item_id, colors.into_iter().collect()))
+            .collect()
+    }
+
+    /// Collapse every Color of an ItemID into a single colorless Item, for
+    /// bulk-buying loose parts regardless of color. The merged Item sums
+    /// the MinQty of each Color, with a missing MinQty counting as 1, and
+    /// keeps the rest of the metadata of the first Item for that ItemID.
+    /// ItemIDs that only appear once pass through untouched, and Items stay
+    /// in the order their ItemID first appears.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let red = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), Some(MinQty(2)));
+    /// let black = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(11)), Some(MinQty(3)));
+    /// let mut wanted_list = WantedList { items: vec![red, black] };
+    /// wanted_list.merge_colors();
+    /// assert_eq!(wanted_list.items.len(), 1);
+    /// assert_eq!(wanted_list.items[0].color, None);
+    /// assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(5)));
+    /// ```
+    pub fn merge_colors(&mut self) {
+        let mut positions: BTreeMap<ItemID, usize> = BTreeMap::new();
+        let mut merged: Vec<Item> = Vec::with_capacity(self.items.len());
+        for item in self.items.drain(..) {
+            match positions.get(&item.item_id) {
+                Some(&position) => {
+                    let merged_item = &mut merged[position];
+                    let merged_qty = merged_item.min_qty.as_ref().map_or(1, |qty| qty.0);
+                    let item_qty = item.min_qty.as_ref().map_or(1, |qty| qty.0);
+                    merged_item.min_qty = Some(MinQty(merged_qty.saturating_add(item_qty)));
+                    merged_item.color = None;
+                }
+                None => {
+                    positions.insert(item.item_id.clone(), merged.len());
+                    merged.push(item);
+                }
+            }
+        }
+        self.items = merged;
+    }
+
+    /// Multiply every Item's MinQty by the factor, e.g. to build several
+    /// copies of the same MOC. A missing MinQty counts as 1 before scaling.
+    /// Quantities saturate at `i32::MAX` rather than overflowing.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor`: What to multiply each MinQty by. Bricklink rejects a
+    ///   MinQty of 0, so callers should refuse a factor of 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     Some(MinQty(4))
+    /// );
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.scale(3);
+    /// assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(12)));
+    /// ```
+    pub fn scale(&mut self, factor: u32) {
+        let factor = i32::try_from(factor).unwrap_or(i32::MAX);
+        for item in self.items.iter_mut() {
+            let min_qty = item.min_qty.as_ref().map_or(1, |min_qty| min_qty.0);
+            item.min_qty = Some(MinQty(min_qty.saturating_mul(factor)));
+        }
+    }
+
+    /// Clamp every Item's MinQty to at most `cap`, e.g. so a join of many
+    /// lists doesn't ask for 500 of a part. Items at or below the cap,
+    /// including Items with no MinQty (which count as 1), are untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `cap`: Largest MinQty to keep
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     Some(MinQty(40))
+    /// );
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.cap_min_qty(25);
+    /// assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(25)));
+    /// ```
+    pub fn cap_min_qty(&mut self, cap: u32) {
+        let cap = i32::try_from(cap).unwrap_or(i32::MAX);
+        for item in self.items.iter_mut() {
+            if let Some(min_qty) = item.min_qty.as_mut() {
+                min_qty.0 = min_qty.0.min(cap);
+            }
+        }
+    }
+
+    /// Build a new WantedList of what is still needed: each Item's MinQty
+    /// is rewritten to its remaining quantity and its QtyFilled is cleared,
+    /// while fully filled Items are dropped. Item order and the remaining
+    /// metadata are preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color, MinQty, QtyFilled};
+    ///
+    /// let mut test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     Some(Color(11)),
+    ///     Some(MinQty(100))
+    /// );
+    /// test_item.qty_filled = Some(QtyFilled(40));
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let remaining = wanted_list.remaining();
+    /// assert_eq!(remaining.items[0].min_qty, Some(MinQty(60)));
+    /// ```
+    pub fn remaining(&self) -> WantedList {
+        WantedList {
+            items: self
+                .items
+                .iter()
+                .filter_map(|item| match item.remaining_qty() {
+                    0 => None,
+                    remaining_qty => {
+                        let mut remaining_item = item.clone();
+                        remaining_item.min_qty = Some(MinQty(remaining_qty));
+                        remaining_item.qty_filled = None;
+                        Some(remaining_item)
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Remove the fully filled Items in place, i.e. those whose QtyFilled is
+    /// at least their MinQty (a missing MinQty counts as 1). Items with no
+    /// QtyFilled are always kept, and unlike `remaining` the quantities of
+    /// the Items that are kept aren't touched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, MinQty, QtyFilled};
+    ///
+    /// let mut test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3622")),
+    ///     None,
+    ///     Some(MinQty(4))
+    /// );
+    /// test_item.qty_filled = Some(QtyFilled(4));
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.prune_filled();
+    /// assert!(wanted_list.is_empty());
+    /// ```
+    pub fn prune_filled(&mut self) {
+        self.retain(|item| item.qty_filled.is_none() || item.remaining_qty() > 0);
+    }
+
+    /// Set the Condition of every Item, replacing any Condition already set.
+    ///
+    /// # Arguments
+    ///
+    /// * `cond`: Condition to give every Item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Condition};
+    ///
+    /// let mut test_item = Item::build_test_item(ItemType::Part, ItemID::from("3622"), None, None);
+    /// test_item.condition = Some(Condition::New);
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.set_condition_all(Condition::Used);
+    /// assert_eq!(wanted_list.items[0].condition, Some(Condition::Used));
+    /// ```
+    pub fn set_condition_all(&mut self, cond: Condition) {
+        for item in self.items.iter_mut() {
+            item.condition = Some(cond.clone());
+        }
+    }
+
+    /// Set the Condition of only the Items that don't have one yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `cond`: Condition to give the Items missing one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Condition};
+    ///
+    /// let mut test_item = Item::build_test_item(ItemType::Part, ItemID::from("3622"), None, None);
+    /// test_item.condition = Some(Condition::New);
+    /// let mut wanted_list = WantedList { items: vec![test_item] };
+    /// wanted_list.set_condition_missing(Condition::Used);
+    /// assert_eq!(wanted_list.items[0].condition, Some(Condition::New));
+    /// ```
+    pub fn set_condition_missing(&mut self, cond: Condition) {
+        for item in self
+            .items
+            .iter_mut()
+            .filter(|item| item.condition.is_none())
+        {
+            item.condition = Some(cond.clone());
+        }
+    }
+
+    /// Sort the Items in place by their canonical ordering (ItemType, then
+    /// ItemID, then Color). The sort is stable, so Items sharing all three
+    /// keep their relative order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID};
+    ///
+    /// let item_1 = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, None);
+    /// let item_2 = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, None);
+    /// let mut wanted_list = WantedList { items: vec![item_1, item_2] };
+    /// wanted_list.sort();
+    /// assert_eq!(wanted_list.items[0].item_id, ItemID(String::from("3001")));
+    /// ```
+    pub fn sort(&mut self) {
+        self.items.sort_by(|a, b| a.canonical_cmp(b));
+    }
+
+    /// Sort the Items in place by a single field, ascending unless
+    /// `descending` is set. Items tied on the field are ordered by ItemID,
+    /// always ascending, and the sort is stable beyond that.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: Field to sort by
+    /// * `descending`: Sort from the largest value of the field down
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, MinQty, SortKey};
+    ///
+    /// let item_1 = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, Some(MinQty(2)));
+    /// let item_2 = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, Some(MinQty(8)));
+    /// let mut wanted_list = WantedList { items: vec![item_1, item_2] };
+    /// wanted_list.sort_by_field(SortKey::Qty, true);
+    /// assert_eq!(wanted_list.items[0].item_id, ItemID(String::from("3622")));
+    /// ```
+    pub fn sort_by_field(&mut self, key: SortKey, descending: bool) {
+        self.items.sort_by(|a, b| {
+            let ordering = key.compare(a, b);
+            let ordering = if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            ordering.then_with(|| a.item_id.cmp(&b.item_id))
+        });
+    }
 
-/// The serde wanted_list of SerdeItems
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename(serialize = "INVENTORY"))]
-pub struct SerdeWantedList {
-    #[serde(rename = "ITEM")]
-    pub items: Vec<SerdeItem>,
-}
+    /// The distinct Colors used by the Items, in Color ID order. Items with
+    /// no Color aren't counted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let item_1 = Item::build_test_item(ItemType::Part, ItemID::from("3001"), Some(Color(11)), None);
+    /// let item_2 = Item::build_test_item(ItemType::Part, ItemID::from("3622"), Some(Color(5)), None);
+    /// let wanted_list = WantedList { items: vec![item_1.clone(), item_2, item_1] };
+    /// let colors: Vec<Color> = wanted_list.colors_used().into_iter().collect();
+    /// assert_eq!(colors, vec![Color(5), Color(11)]);
+    /// ```
+    pub fn colors_used(&self) -> BTreeSet<Color> {
+        self.group_by(|item| item.color.clone())
+            .into_keys()
+            .flatten()
+            .collect()
+    }
 
-impl SerdeWantedList {
-    /// Dirty fix for a serialization issue with the quick_xml library.
-    /// When we try to serialize a Vec<SerdeItem>, we end up with
-    /// <ITEM><ITEM>...</ITEM></ITEM> at the beginning and end of the
-    /// vectors. So...we're going to straight up remove the redundant
-    /// Items by replacing those ranges in the String.
+    /// Find every Item whose Color isn't in the Bricklink color catalog.
+    /// Items without a Color are never reported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
+    ///
+    /// let item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(9999)), None);
+    /// let wanted_list = WantedList { items: vec![item] };
+    /// assert_eq!(
+    ///     wanted_list.validate_colors(),
+    ///     vec![(ItemID(String::from("3001")), Color(9999))]
+    /// );
+    /// ```
+    pub fn validate_colors(&self) -> Vec<(ItemID, Color)> {
+        self.items
+            .iter()
+            .filter_map(|item| match &item.color {
+                Some(color) if !color.is_valid() => Some((item.item_id.clone(), color.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A fingerprint of the WantedList's content, for telling whether two
+    /// files hold the same logical list. Every field of every Item counts,
+    /// but Item order and XML formatting don't. This is a 64 bit FNV-1a
+    /// hash written as 16 hex digits: good for change detection, not for
+    /// anything security related.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID};
     ///
+    /// let item_1 = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, None);
+    /// let item_2 = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, None);
+    /// let wanted_list = WantedList { items: vec![item_1.clone(), item_2.clone()] };
+    /// let reversed = WantedList { items: vec![item_2, item_1] };
+    /// assert_eq!(wanted_list.fingerprint(), reversed.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> String {
+        let mut lines: Vec<String> = self.items.iter().map(fingerprint_line).collect();
+        lines.sort();
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in lines.join("\n").bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+/// Every field of an Item in its serialized form, separated by tabs, so
+/// that equal Items give equal lines whatever order they're in
+fn fingerprint_line(item: &Item) -> String {
+    let serde_item = SerdeItem::from(item.clone());
+    let mut fields = vec![serde_item.item_type, serde_item.item_id];
+    fields.extend(
+        vec![
+            serde_item.color.map(|color| color.to_string()),
+            serde_item.max_price,
+            serde_item.min_qty.map(|min_qty| min_qty.to_string()),
+            serde_item
+                .qty_filled
+                .map(|qty_filled| qty_filled.to_string()),
+            serde_item.condition,
+            serde_item.remarks,
+            serde_item.notify,
+            serde_item.wanted_show,
+            serde_item.wanted_list_id,
+            serde_item.alternate,
+            serde_item.counterpart,
+        ]
+        .into_iter()
+        .map(Option::unwrap_or_default),
+    );
+    for (name, value) in serde_item.extra {
+        fields.push(format!("{}={}", name, value));
+    }
+    fields.join("\t")
+}
+
+/// Collect Items into a WantedList, e.g. after mapping or filtering
+///
+/// # Example
+///
+/// ```
+/// use brickline::wanted::{WantedList, Item, ItemType, ItemID};
+///
+/// let wanted_list: WantedList = vec!["3001", "3622"]
+///     .into_iter()
+///     .map(|item_id| Item::build_test_item(ItemType::Part, ItemID(String::from(item_id)), None, None))
+///     .collect();
+/// assert_eq!(wanted_list.items.len(), 2);
+/// ```
+impl std::iter::FromIterator<Item> for WantedList {
+    fn from_iter<I: IntoIterator<Item = Item>>(iter: I) -> Self {
+        WantedList {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for WantedList {
+    type Item = Item;
+    type IntoIter = std::vec::IntoIter<Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a WantedList {
+    type Item = &'a Item;
+    type IntoIter = std::slice::Iter<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+/// Append Items to the end of a WantedList
+impl Extend<Item> for WantedList {
+    fn extend<I: IntoIterator<Item = Item>>(&mut self, iter: I) {
+        self.items.extend(iter)
+    }
+}
+
+/// How each level of pretty-printed XML is indented
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Indent {
+    /// This many spaces, 2 by default
+    Spaces(usize),
+    /// One tab
+    Tab,
+}
+
+impl Default for Indent {
+    fn default() -> Indent {
+        Indent::Spaces(2)
+    }
+}
+
+impl std::str::FromStr for Indent {
+    type Err = std::io::Error;
+
+    fn from_str(indent_str: &str) -> Result<Self, Self::Err> {
+        match indent_str {
+            "tab" => Ok(Self::Tab),
+            width => width.parse::<usize>().map(Self::Spaces).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("{} is not a supported indent", width),
+                )
+            }),
+        }
+    }
+}
+
+/// Serialize an WantedList to an XML String
+impl WantedList {
+    /// Convert the WantedList to an XML string, indented with one element
+    /// per line if `pretty` is set. Otherwise this is the same compact
+    /// single line as `String::try_from`.
     ///
     /// # Arguments
     ///
-    /// * `serde_string`: Serialized String of a SerdeWantedList
+    /// * `pretty`: Indent the XML for reading and diffing
     ///
     /// # Example
     ///
     /// ```
-    /// use quick_xml::se::to_string;
-    /// use brickline::wanted::{WantedList, SerdeWantedList, Item, ItemType,
-    ///                         ItemID, Color};
+    /// use brickline::wanted::{WantedList, Item, ItemType, ItemID, Color};
     ///
     /// let test_item = Item::build_test_item(
     ///     ItemType::Part,
@@ -49,28 +957,79 @@ impl SerdeWantedList {
     ///     Some(Color(11)),
     ///     None
     /// );
-    /// let wanted_list = WantedList { items: vec![test_item]};
-    /// let serde_wanted_list = SerdeWantedList::from(wanted_list);
-    /// let stringified = to_string(&serde_wanted_list).unwrap();
-    /// let repaired = SerdeWantedList::amend_serialized_string(stringified);
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let pretty = wanted_list.to_xml(true).unwrap();
+    /// assert!(pretty.contains("\n  <ITEM>\n    <ITEMTYPE>P</ITEMTYPE>"));
+    /// ```
+    pub fn to_xml(self, pretty: bool) -> Result<String, DeError> {
+        self.to_xml_indented(if pretty {
+            Some(Indent::default())
+        } else {
+            None
+        })
+    }
+
+    /// Convert the WantedList to an XML string, indented with one element
+    /// per line using the given indentation, or as a compact single line if
+    /// there is none.
+    ///
+    /// # Arguments
+    ///
+    /// * `indent`: How to indent each level of the XML, if at all
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Indent, WantedList, Item, ItemType, ItemID};
+    ///
+    /// let test_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3622")), None, None);
+    /// let wanted_list = WantedList { items: vec![test_item] };
+    /// let tabbed = wanted_list.to_xml_indented(Some(Indent::Tab)).unwrap();
+    /// assert!(tabbed.contains("\n\t<ITEM>\n\t\t<ITEMTYPE>P</ITEMTYPE>"));
     /// ```
-    pub fn amend_serialized_string(mut serde_string: String) -> String {
-        serde_string.replace_range(11..17, "");
-        let end_bound_1 = serde_string.len() - 19;
-        let end_bound_2 = serde_string.len() - 12;
-        serde_string.replace_range(end_bound_1..end_bound_2, "");
-        serde_string.insert_str(0, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
-        serde_string
+    pub fn to_xml_indented(self, indent: Option<Indent>) -> Result<String, DeError> {
+        let compact = String::try_from(self)?;
+        let (indent_char, indent_size) = match indent {
+            None => return Ok(compact),
+            Some(Indent::Spaces(width)) => (b' ', width),
+            Some(Indent::Tab) => (b'\t', 1),
+        };
+        // Copy the events over to a Writer that indents. The text is copied
+        // still escaped, so nothing is lost along the way. The Reader emits
+        // empty text between adjacent tags, which would stop the Writer from
+        // breaking the line, so those are skipped.
+        let mut reader = Reader::from_str(&compact);
+        let mut writer = Writer::new_with_indent(Vec::new(), indent_char, indent_size);
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event(&mut buf).map_err(DeError::Xml)? {
+                Event::Eof => break,
+                Event::Text(ref text) if text.is_empty() => (),
+                event => {
+                    writer.write_event(event).map_err(DeError::Xml)?;
+                }
+            }
+            buf.clear();
+        }
+        let mut pretty_bytes = writer.into_inner();
+        pretty_bytes.push(b'\n');
+        String::from_utf8(pretty_bytes).map_err(|e| DeError::Custom(e.to_string()))
     }
 }
 
-/// A Bricklink WantedList
-#[derive(Debug, PartialEq)]
-pub struct WantedList {
-    pub items: Vec<Item>,
+/// One line per Item, in list order (see the Display for Item)
+impl std::fmt::Display for WantedList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
 }
 
-/// Serialize an WantedList to an XML String
 impl std::convert::TryFrom<WantedList> for String {
     type Error = DeError;
 
@@ -90,14 +1049,147 @@ impl std::convert::TryFrom<WantedList> for String {
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// What an Item with no MinQty counts as when quantities are summed, in a
+/// join or in the statistics. Bricklink treats a missing MinQty as 1, which
+/// is the default, but some lists leave it out to mean "as many as are
+/// available" and want those Items left out of part sums instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DefaultQty {
+    /// A missing MinQty counts as 1
+    #[default]
+    One,
+    /// A missing MinQty counts as 0, so the Item adds nothing to a sum
+    Exclude,
+}
+
+impl DefaultQty {
+    /// The quantity to use for a MinQty, falling back to this default when
+    /// it's missing
+    ///
+    /// # Arguments
+    ///
+    /// * `min_qty`: MinQty of an Item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{DefaultQty, MinQty};
+    ///
+    /// assert_eq!(DefaultQty::One.qty(&None), 1);
+    /// assert_eq!(DefaultQty::Exclude.qty(&None), 0);
+    /// assert_eq!(DefaultQty::Exclude.qty(&Some(MinQty(4))), 4);
+    /// ```
+    pub fn qty(self, min_qty: &Option<MinQty>) -> i32 {
+        match (min_qty, self) {
+            (Some(min_qty), _) => min_qty.0,
+            (None, DefaultQty::One) => 1,
+            (None, DefaultQty::Exclude) => 0,
+        }
+    }
+}
+
+impl std::str::FromStr for DefaultQty {
+    type Err = std::io::Error;
+
+    fn from_str(default_str: &str) -> Result<Self, Self::Err> {
+        match default_str {
+            "one" => Ok(Self::One),
+            "exclude" => Ok(Self::Exclude),
+            unsupported => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is not a supported default quantity", unsupported),
+            )),
+        }
+    }
+}
+
+/// A field to sort a WantedList by with `WantedList::sort_by_field`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    /// ItemID
+    Id,
+    /// MinQty, with a missing MinQty first
+    Qty,
+    /// Color, with colorless Items first
+    Color,
+    /// ItemType
+    Type,
+    /// MaxPrice, with a missing MaxPrice first
+    Price,
+}
+
+impl SortKey {
+    /// Compare two Items on this field alone
+    ///
+    /// # Arguments
+    ///
+    /// * `a`: Item to compare
+    /// * `b`: Item to compare it against
+    ///
+    pub fn compare(self, a: &Item, b: &Item) -> Ordering {
+        match self {
+            SortKey::Id => a.item_id.cmp(&b.item_id),
+            SortKey::Qty => {
+                let qty = |item: &Item| item.min_qty.as_ref().map(|min_qty| min_qty.0);
+                qty(a).cmp(&qty(b))
+            }
+            SortKey::Color => a.color.cmp(&b.color),
+            SortKey::Type => a.item_type.cmp(&b.item_type),
+            SortKey::Price => {
+                let price = |item: &Item| item.max_price.as_ref().map(|max_price| max_price.0);
+                price(a).cmp(&price(b))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = std::io::Error;
+
+    fn from_str(key_str: &str) -> Result<Self, Self::Err> {
+        match key_str {
+            "id" => Ok(Self::Id),
+            "qty" => Ok(Self::Qty),
+            "color" => Ok(Self::Color),
+            "type" => Ok(Self::Type),
+            "price" => Ok(Self::Price),
+            unsupported => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is not a supported sort key", unsupported),
+            )),
+        }
+    }
+}
+
+/// Summary statistics of a WantedList. Serializing them (e.g. to JSON) only
+/// writes out the counts; the sets used to build the unique counts are
+/// skipped.
+#[derive(Debug, PartialEq, Serialize)]
 pub struct WantedListStatistics {
     pub total_items: i32,
     pub total_parts: i32,
+    /// Parts still needed once QtyFilled is taken off each Item's MinQty
+    /// (see `Item::remaining_qty`)
+    pub total_parts_remaining: i32,
     pub unique_item_color_count: i32,
     pub unique_color_count: i32,
+    /// Sum of max_price * min_qty over the priced Items
+    pub total_max_price: MaxPrice,
+    /// Items with a max price, which count towards total_max_price
+    pub priced_item_count: i32,
+    /// Items with no max price (or Bricklink's negative "no maximum"
+    /// price), which are left out of total_max_price
+    pub unpriced_item_count: i32,
+    /// Number of Items of each ItemType
+    pub counts_by_type: BTreeMap<ItemType, i32>,
+    /// Number of Items of each Condition, with Items that don't specify one
+    /// under None
+    #[serde(serialize_with = "serialize_condition_counts")]
+    pub counts_by_condition: BTreeMap<Option<Condition>, i32>,
 
+    #[serde(skip)]
     pub item_color_set: HashSet<OwnedItemColorHashKey>,
+    #[serde(skip)]
     pub color_set: HashSet<Color>,
 }
 
@@ -106,14 +1198,41 @@ impl WantedListStatistics {
         WantedListStatistics {
             total_items: 0,
             total_parts: 0,
+            total_parts_remaining: 0,
             unique_item_color_count: 0,
             unique_color_count: 0,
+            total_max_price: MaxPrice(0),
+            priced_item_count: 0,
+            unpriced_item_count: 0,
+            counts_by_type: BTreeMap::new(),
+            counts_by_condition: BTreeMap::new(),
             item_color_set: HashSet::new(),
             color_set: HashSet::new(),
         }
     }
 }
 
+/// Label for the Items with no Condition in the condition counts
+const UNSPECIFIED_CONDITION: &str = "Unspecified";
+
+/// Serialize the condition counts keyed on the Condition names, with the
+/// Items that don't specify one under "Unspecified"
+fn serialize_condition_counts<S>(
+    counts_by_condition: &BTreeMap<Option<Condition>, i32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_map(counts_by_condition.iter().map(|(condition, count)| {
+        let label = match condition {
+            Some(condition) => format!("{:?}", condition),
+            None => String::from(UNSPECIFIED_CONDITION),
+        };
+        (label, count)
+    }))
+}
+
 impl std::fmt::Display for WantedListStatistics {
     // This trait requires `fmt` with this exact signature.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -122,110 +1241,424 @@ impl std::fmt::Display for WantedListStatistics {
             "
 Total Items: {}, 
 Total Parts: {}, 
+Total Parts Remaining: {}, 
 Unique Item/Color Count: {}, 
-Unique Color Count: {}",
+Unique Color Count: {}, 
+Total Max Price: {}, 
+Priced Item Count: {}, 
+Unpriced Item Count: {}, 
+Item Counts By Type: {}, 
+Item Counts By Condition: {}",
             self.total_items,
             self.total_parts,
+            self.total_parts_remaining,
             self.unique_item_color_count,
-            self.unique_color_count
+            self.unique_color_count,
+            String::from(self.total_max_price.clone()),
+            self.priced_item_count,
+            self.unpriced_item_count,
+            self.counts_by_type
+                .iter()
+                .map(|(item_type, count)| format!("{:?} ({})", item_type, count))
+                .collect::<Vec<String>>()
+                .join(", "),
+            // Unspecified sorts first in the map, but reads better last
+            self.counts_by_condition
+                .iter()
+                .filter_map(|(condition, count)| condition
+                    .as_ref()
+                    .map(|condition| format!("{:?} ({})", condition, count)))
+                .chain(
+                    self.counts_by_condition
+                        .get(&None)
+                        .map(|count| format!("{} ({})", UNSPECIFIED_CONDITION, count))
+                )
+                .collect::<Vec<String>>()
+                .join(", ")
         )
     }
 }
 
 /// The primary key of an WantedList Item
-#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct OwnedItemColorHashKey {
-    item_id: ItemID,
-    color: Option<Color>,
+    pub item_id: ItemID,
+    pub color: Option<Color>,
+}
+
+impl std::convert::From<&Item> for OwnedItemColorHashKey {
+    fn from(item: &Item) -> OwnedItemColorHashKey {
+        let (item_id, color) = item.color_key();
+        OwnedItemColorHashKey { item_id, color }
+    }
 }
 
-pub fn update_wanted_list_statistic(item: &Item, aggregate: &mut WantedListStatistics) -> () {
+pub fn update_wanted_list_statistic(item: &Item, aggregate: &mut WantedListStatistics) {
+    update_wanted_list_statistic_with(item, aggregate, DefaultQty::One)
+}
+
+/// Like `update_wanted_list_statistic`, but with the given count for an
+/// Item with no MinQty in the part sums
+pub fn update_wanted_list_statistic_with(
+    item: &Item,
+    aggregate: &mut WantedListStatistics,
+    default_qty: DefaultQty,
+) {
     aggregate.total_items += 1;
 
-    match &item.min_qty {
-        Some(min_qty) => aggregate.total_parts += min_qty.0,
-        None => aggregate.total_parts += 1,
-    };
+    let min_qty = default_qty.qty(&item.min_qty);
+    aggregate.total_parts = aggregate.total_parts.saturating_add(min_qty);
+    let qty_filled = item.qty_filled.as_ref().map_or(0, |qty| qty.0.max(0));
+    aggregate.total_parts_remaining = aggregate
+        .total_parts_remaining
+        .saturating_add(min_qty.saturating_sub(qty_filled).max(0));
 
-    let ic_hk = OwnedItemColorHashKey {
-        item_id: item.item_id.clone(),
-        color: item.color.clone(),
-    };
+    let ic_hk = OwnedItemColorHashKey::from(item);
 
     if !aggregate.item_color_set.contains(&ic_hk) {
         aggregate.unique_item_color_count += 1;
         aggregate.item_color_set.insert(ic_hk);
     }
 
-    item.color.as_ref().map(|color| {
+    if let Some(color) = &item.color {
         if !aggregate.color_set.contains(color) {
             aggregate.unique_color_count += 1;
             aggregate.color_set.insert(color.clone());
         }
-    });
+    }
+
+    match &item.max_price {
+        Some(max_price) if max_price.0 >= 0 => {
+            let item_total = max_price.0.saturating_mul(i64::from(min_qty));
+            aggregate.total_max_price.0 = aggregate.total_max_price.0.saturating_add(item_total);
+            aggregate.priced_item_count += 1;
+        }
+        _ => aggregate.unpriced_item_count += 1,
+    }
+
+    *aggregate
+        .counts_by_type
+        .entry(item.item_type.clone())
+        .or_insert(0) += 1;
+
+    *aggregate
+        .counts_by_condition
+        .entry(item.condition.clone())
+        .or_insert(0) += 1;
 }
 
 pub fn type_and_gen_statistics(
     serde_wanted_list: SerdeWantedList,
-) -> (WantedList, WantedListStatistics) {
-    let mut statistics = WantedListStatistics {
-        total_items: 0,
-        total_parts: 0,
-        unique_item_color_count: 0,
-        unique_color_count: 0,
-        item_color_set: HashSet::new(),
-        color_set: HashSet::new(),
-    };
+) -> Result<(WantedList, WantedListStatistics), ParseError> {
+    let mut statistics = WantedListStatistics::init();
 
     let items = serde_wanted_list
         .items
         .into_iter()
         .map(|i| {
-            let item = Item::from(i);
+            let item = Item::try_from(i)?;
             update_wanted_list_statistic(&item, &mut statistics);
-            item
+            Ok(item)
         })
-        .collect();
+        .collect::<Result<Vec<Item>, ParseError>>()?;
 
-    (WantedList { items: items }, statistics)
+    Ok((WantedList { items }, statistics))
 }
 
 // TODO: Unify the above and below
 pub fn gen_statistics(wanted_list: &WantedList) -> WantedListStatistics {
+    gen_statistics_with(wanted_list, DefaultQty::One)
+}
+
+/// Like `gen_statistics`, but with the given count for an Item with no
+/// MinQty in the part sums
+///
+/// # Arguments
+///
+/// * `wanted_list`: WantedList to summarize
+/// * `default_qty`: What an Item with no MinQty counts as
+///
+pub fn gen_statistics_with(
+    wanted_list: &WantedList,
+    default_qty: DefaultQty,
+) -> WantedListStatistics {
     let mut statistics = WantedListStatistics::init();
     wanted_list
-        .items
         .iter()
-        .for_each(|item| update_wanted_list_statistic(item, &mut statistics));
+        .for_each(|item| update_wanted_list_statistic_with(item, &mut statistics, default_qty));
     statistics
 }
 
+/// A problem with a single Item that would stop Bricklink from accepting it
+#[derive(Debug, PartialEq)]
+pub enum ItemProblem {
+    UnknownItemType(String),
+    EmptyItemId,
+    UnknownColor(i32),
+    NonPositiveMinQty(i32),
+    QtyFilledExceedsMinQty { qty_filled: i32, min_qty: i32 },
+    UnparseableMaxPrice(String),
+}
+
+impl std::fmt::Display for ItemProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ItemProblem::UnknownItemType(item_type) => {
+                write!(f, "unknown item type \"{}\"", item_type)
+            }
+            ItemProblem::EmptyItemId => write!(f, "empty item ID"),
+            ItemProblem::UnknownColor(color) => write!(f, "unknown color ID {}", color),
+            ItemProblem::NonPositiveMinQty(min_qty) => {
+                write!(f, "minimum quantity {} is not positive", min_qty)
+            }
+            ItemProblem::QtyFilledExceedsMinQty {
+                qty_filled,
+                min_qty,
+            } => write!(
+                f,
+                "quantity filled {} exceeds minimum quantity {}",
+                qty_filled, min_qty
+            ),
+            ItemProblem::UnparseableMaxPrice(max_price) => {
+                write!(f, "could not parse max price \"{}\"", max_price)
+            }
+        }
+    }
+}
+
+/// A problem with a wanted list that would stop Bricklink from accepting it.
+/// `index` is the position of the offending <ITEM> in the list, from 0.
+#[derive(Debug, PartialEq)]
+pub enum ValidationError {
+    UnknownItemType {
+        index: usize,
+        item_id: String,
+        item_type: String,
+    },
+    EmptyItemId {
+        index: usize,
+    },
+    UnknownColor {
+        index: usize,
+        item_id: String,
+        color: i32,
+    },
+    NonPositiveMinQty {
+        index: usize,
+        item_id: String,
+        min_qty: i32,
+    },
+    QtyFilledExceedsMinQty {
+        index: usize,
+        item_id: String,
+        qty_filled: i32,
+        min_qty: i32,
+    },
+    UnparseableMaxPrice {
+        index: usize,
+        item_id: String,
+        max_price: String,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ValidationError::UnknownItemType {
+                index,
+                item_id,
+                item_type,
+            } => write!(
+                f,
+                "Item {} ({}): unknown item type \"{}\"",
+                index, item_id, item_type
+            ),
+            ValidationError::EmptyItemId { index } => write!(f, "Item {}: empty item ID", index),
+            ValidationError::UnknownColor {
+                index,
+                item_id,
+                color,
+            } => write!(
+                f,
+                "Item {} ({}): unknown color ID {}",
+                index, item_id, color
+            ),
+            ValidationError::NonPositiveMinQty {
+                index,
+                item_id,
+                min_qty,
+            } => write!(
+                f,
+                "Item {} ({}): minimum quantity {} is not positive",
+                index, item_id, min_qty
+            ),
+            ValidationError::QtyFilledExceedsMinQty {
+                index,
+                item_id,
+                qty_filled,
+                min_qty,
+            } => write!(
+                f,
+                "Item {} ({}): quantity filled {} exceeds minimum quantity {}",
+                index, item_id, qty_filled, min_qty
+            ),
+            ValidationError::UnparseableMaxPrice {
+                index,
+                item_id,
+                max_price,
+            } => write!(
+                f,
+                "Item {} ({}): could not parse max price \"{}\"",
+                index, item_id, max_price
+            ),
+        }
+    }
+}
+
+/// Check every Item of a wanted list for problems, collecting all of them
+/// rather than stopping at the first. This works on the SerdeWantedList
+/// because some problems (an unknown item type, an unparseable max price)
+/// can't be represented once the list is converted to a WantedList.
+///
+/// QtyFilled is only checked against MinQty when both are present.
+///
+/// # Arguments
+///
+/// * `serde_wanted_list`: Deserialized wanted list to check
+///
+/// # Example
+///
+/// ```
+/// use brickline::wanted::{validate, SerdeWantedList};
+/// use quick_xml::de::from_str;
+///
+/// let xml = "<INVENTORY><ITEM><ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID></ITEM></INVENTORY>";
+/// let serde_wanted_list = from_str::<SerdeWantedList>(xml).unwrap();
+/// assert!(validate(&serde_wanted_list).is_empty());
+/// ```
+pub fn validate(serde_wanted_list: &SerdeWantedList) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    for (index, item) in serde_wanted_list.items.iter().enumerate() {
+        let item_id = item.item_id.clone();
+        errors.extend(item.problems().into_iter().map(|problem| match problem {
+            ItemProblem::UnknownItemType(item_type) => ValidationError::UnknownItemType {
+                index,
+                item_id: item_id.clone(),
+                item_type,
+            },
+            ItemProblem::EmptyItemId => ValidationError::EmptyItemId { index },
+            ItemProblem::UnknownColor(color) => ValidationError::UnknownColor {
+                index,
+                item_id: item_id.clone(),
+                color,
+            },
+            ItemProblem::NonPositiveMinQty(min_qty) => ValidationError::NonPositiveMinQty {
+                index,
+                item_id: item_id.clone(),
+                min_qty,
+            },
+            ItemProblem::QtyFilledExceedsMinQty {
+                qty_filled,
+                min_qty,
+            } => ValidationError::QtyFilledExceedsMinQty {
+                index,
+                item_id: item_id.clone(),
+                qty_filled,
+                min_qty,
+            },
+            ItemProblem::UnparseableMaxPrice(max_price) => ValidationError::UnparseableMaxPrice {
+                index,
+                item_id: item_id.clone(),
+                max_price,
+            },
+        }));
+    }
+    errors
+}
+
 impl std::convert::From<WantedList> for SerdeWantedList {
     fn from(wanted_list: WantedList) -> SerdeWantedList {
         SerdeWantedList {
-            items: wanted_list
-                .items
-                .into_iter()
-                .map(|i| SerdeItem::from(i))
-                .collect(),
+            items: wanted_list.items.into_iter().map(SerdeItem::from).collect(),
         }
     }
 }
 
-impl std::convert::From<SerdeWantedList> for WantedList {
-    fn from(serde_wanted_list: SerdeWantedList) -> WantedList {
-        WantedList {
+impl std::convert::TryFrom<SerdeWantedList> for WantedList {
+    type Error = ParseError;
+
+    fn try_from(serde_wanted_list: SerdeWantedList) -> Result<Self, Self::Error> {
+        Ok(WantedList {
             items: serde_wanted_list
                 .items
                 .into_iter()
-                .map(|i| Item::from(i))
-                .collect(),
+                .map(Item::try_from)
+                .collect::<Result<Vec<Item>, ParseError>>()?,
+        })
+    }
+}
+
+impl SerdeItem {
+    /// Check the Item for problems that would stop Bricklink from accepting
+    /// it, collecting all of them rather than stopping at the first. This
+    /// is the per-Item check behind `validate` and `Item::validate`.
+    /// QtyFilled is only checked against MinQty when both are present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{ItemProblem, SerdeItem};
+    ///
+    /// let serde_item = SerdeItem {
+    ///     item_type: String::from("P"),
+    ///     item_id: String::from("3001"),
+    ///     max_price: Some(String::from("cheap")),
+    ///     ..SerdeItem::default()
+    /// };
+    /// assert_eq!(
+    ///     serde_item.problems(),
+    ///     vec![ItemProblem::UnparseableMaxPrice(String::from("cheap"))]
+    /// );
+    /// ```
+    pub fn problems(&self) -> Vec<ItemProblem> {
+        let mut problems = Vec::new();
+        if ItemType::from_code(&self.item_type).is_none() {
+            problems.push(ItemProblem::UnknownItemType(self.item_type.clone()));
         }
+        if self.item_id.trim().is_empty() {
+            problems.push(ItemProblem::EmptyItemId);
+        }
+        if let Some(color) = self.color {
+            if !Color(color).is_valid() {
+                problems.push(ItemProblem::UnknownColor(color));
+            }
+        }
+        if let Some(min_qty) = self.min_qty {
+            if min_qty <= 0 {
+                problems.push(ItemProblem::NonPositiveMinQty(min_qty));
+            }
+        }
+        if let (Some(qty_filled), Some(min_qty)) = (self.qty_filled, self.min_qty) {
+            if qty_filled > min_qty {
+                problems.push(ItemProblem::QtyFilledExceedsMinQty {
+                    qty_filled,
+                    min_qty,
+                });
+            }
+        }
+        if let Some(max_price) = &self.max_price {
+            if MaxPrice::parse_cents(max_price).is_none() {
+                problems.push(ItemProblem::UnparseableMaxPrice(max_price.clone()));
+            }
+        }
+        problems
     }
 }
 
-/// A serde representation of an Item
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+/// A serde representation of an Item. Deserialize is implemented by hand
+/// so that child elements we don't model are kept in `extra` rather than
+/// dropped.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename(serialize = "ITEM"))]
 pub struct SerdeItem {
     #[serde(rename = "ITEMTYPE")]
@@ -234,7 +1667,7 @@ pub struct SerdeItem {
     pub item_id: String,
     #[serde(rename = "COLOR")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub color: Option<i8>,
+    pub color: Option<i32>,
     #[serde(rename = "MAXPRICE")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_price: Option<String>,
@@ -259,6 +1692,255 @@ pub struct SerdeItem {
     #[serde(rename = "WANTEDLISTID")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wanted_list_id: Option<String>,
+    #[serde(rename = "ALTERNATE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate: Option<String>,
+    #[serde(rename = "COUNTERPART")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counterpart: Option<String>,
+    /// Child elements of the ITEM that aren't in the schema, keyed on their
+    /// element name, so they survive a round trip. Written back out after
+    /// the known fields (see `amend_serialized_string`).
+    #[serde(rename = "BRICKLINE_EXTRA")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl<'de> Deserialize<'de> for SerdeItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("ITEM", &[], SerdeItemVisitor)
+    }
+}
+
+/// The value of an unknown ITEM child element: its text if it holds plain
+/// text, or nothing if it holds nested elements, which are skipped. It's
+/// read as a struct with a `$value` field, which is how quick-xml hands over
+/// the content of an element, whether text or child elements.
+enum ExtraValue {
+    Text(String),
+    Nested,
+}
+
+impl<'de> Deserialize<'de> for ExtraValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("EXTRA", &["$value"], ExtraValueVisitor)
+    }
+}
+
+struct ExtraValueVisitor;
+
+impl<'de> serde::de::Visitor<'de> for ExtraValueVisitor {
+    type Value = ExtraValue;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("text or nested elements")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ExtraValue, E> {
+        Ok(ExtraValue::Text(String::from(value)))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, value: String) -> Result<ExtraValue, E> {
+        Ok(ExtraValue::Text(value))
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<ExtraValue, E> {
+        Ok(ExtraValue::Text(String::new()))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<ExtraValue, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut value = ExtraValue::Text(String::new());
+        while let Some(key) = map.next_key::<String>()? {
+            match (key.as_str(), &value) {
+                ("$value", ExtraValue::Text(text)) if text.is_empty() => {
+                    value = map.next_value::<ExtraContent>()?.0;
+                }
+                _ => {
+                    map.next_value::<serde::de::IgnoredAny>()?;
+                    value = ExtraValue::Nested;
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// The content of an unknown element: text, or a child element, which is
+/// drained and skipped
+struct ExtraContent(ExtraValue);
+
+impl<'de> Deserialize<'de> for ExtraContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ExtraContentVisitor)
+    }
+}
+
+struct ExtraContentVisitor;
+
+impl<'de> serde::de::Visitor<'de> for ExtraContentVisitor {
+    type Value = ExtraContent;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("text or an element")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ExtraContent, E> {
+        Ok(ExtraContent(ExtraValue::Text(String::from(value))))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, value: String) -> Result<ExtraContent, E> {
+        Ok(ExtraContent(ExtraValue::Text(value)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<ExtraContent, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        while map
+            .next_entry::<serde::de::IgnoredAny, serde::de::IgnoredAny>()?
+            .is_some()
+        {}
+        Ok(ExtraContent(ExtraValue::Nested))
+    }
+}
+
+/// Reads the child elements of an ITEM into a SerdeItem. Unknown elements
+/// holding plain text are kept in `extra`, and unknown elements holding
+/// nested elements are skipped; the last of any repeated element wins.
+struct SerdeItemVisitor;
+
+impl<'de> serde::de::Visitor<'de> for SerdeItemVisitor {
+    type Value = SerdeItem;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an ITEM element")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<SerdeItem, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut item_type = None;
+        let mut item_id = None;
+        let mut serde_item = SerdeItem::default();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "ITEMTYPE" => item_type = Some(map.next_value()?),
+                "ITEMID" => item_id = Some(map.next_value()?),
+                "COLOR" => serde_item.color = map.next_value()?,
+                "MAXPRICE" => serde_item.max_price = map.next_value()?,
+                "MINQTY" => serde_item.min_qty = map.next_value()?,
+                "QTYFILLED" => serde_item.qty_filled = map.next_value()?,
+                "CONDITION" => serde_item.condition = map.next_value()?,
+                "REMARKS" => serde_item.remarks = map.next_value()?,
+                "NOTIFY" => serde_item.notify = map.next_value()?,
+                "WANTEDSHOW" => serde_item.wanted_show = map.next_value()?,
+                "WANTEDLISTID" => serde_item.wanted_list_id = map.next_value()?,
+                "ALTERNATE" => serde_item.alternate = map.next_value()?,
+                "COUNTERPART" => serde_item.counterpart = map.next_value()?,
+                // Only formats that keep the wrapper (e.g. JSON) see this
+                "BRICKLINE_EXTRA" => serde_item
+                    .extra
+                    .extend(map.next_value::<BTreeMap<String, String>>()?),
+                _ => {
+                    if let ExtraValue::Text(value) = map.next_value::<ExtraValue>()? {
+                        serde_item.extra.insert(key, value);
+                    }
+                }
+            }
+        }
+        serde_item.item_type =
+            item_type.ok_or_else(|| serde::de::Error::missing_field("ITEMTYPE"))?;
+        serde_item.item_id = item_id.ok_or_else(|| serde::de::Error::missing_field("ITEMID"))?;
+        Ok(serde_item)
+    }
+}
+
+/// The JSON representation of an Item used by `Item::to_json`. The fields
+/// match SerdeItem, but with readable names instead of the XML element
+/// names.
+#[derive(Debug, Deserialize, Serialize)]
+struct JsonItem {
+    item_type: String,
+    item_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_price: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min_qty: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    qty_filled: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remarks: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notify: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wanted_show: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wanted_list_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alternate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    counterpart: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    extra: BTreeMap<String, String>,
+}
+
+impl From<SerdeItem> for JsonItem {
+    fn from(serde_item: SerdeItem) -> JsonItem {
+        JsonItem {
+            item_type: serde_item.item_type,
+            item_id: serde_item.item_id,
+            color: serde_item.color,
+            max_price: serde_item.max_price,
+            min_qty: serde_item.min_qty,
+            qty_filled: serde_item.qty_filled,
+            condition: serde_item.condition,
+            remarks: serde_item.remarks,
+            notify: serde_item.notify,
+            wanted_show: serde_item.wanted_show,
+            wanted_list_id: serde_item.wanted_list_id,
+            alternate: serde_item.alternate,
+            counterpart: serde_item.counterpart,
+            extra: serde_item.extra,
+        }
+    }
+}
+
+impl From<JsonItem> for SerdeItem {
+    fn from(json_item: JsonItem) -> SerdeItem {
+        SerdeItem {
+            item_type: json_item.item_type,
+            item_id: json_item.item_id,
+            color: json_item.color,
+            max_price: json_item.max_price,
+            min_qty: json_item.min_qty,
+            qty_filled: json_item.qty_filled,
+            condition: json_item.condition,
+            remarks: json_item.remarks,
+            notify: json_item.notify,
+            wanted_show: json_item.wanted_show,
+            wanted_list_id: json_item.wanted_list_id,
+            alternate: json_item.alternate,
+            counterpart: json_item.counterpart,
+            extra: json_item.extra,
+        }
+    }
 }
 
 /// A single Lego Item
@@ -275,23 +1957,44 @@ pub struct Item {
     pub notify: Option<Notify>,
     pub wanted_show: Option<WantedShow>,
     pub wanted_list_id: Option<WantedListID>,
+    /// Y/N flag for whether the Item is an alternate
+    pub alternate: Option<Notify>,
+    /// Y/N flag for whether the Item is a counterpart
+    pub counterpart: Option<Notify>,
+    /// Elements we don't model, kept as they were read (see `SerdeItem`)
+    pub extra: BTreeMap<String, String>,
 }
 
-impl std::convert::From<SerdeItem> for Item {
-    fn from(serde_item: SerdeItem) -> Item {
-        Item {
-            item_type: ItemType::from(serde_item.item_type),
+impl std::convert::TryFrom<SerdeItem> for Item {
+    type Error = ParseError;
+
+    fn try_from(serde_item: SerdeItem) -> Result<Self, Self::Error> {
+        // Notify is shared by the Y/N flags, so name the field that failed
+        let flag = |field: &'static str, value: Option<String>| {
+            value
+                .map(Notify::try_from)
+                .transpose()
+                .map_err(|e| ParseError { field, ..e })
+        };
+        Ok(Item {
+            item_type: ItemType::try_from(serde_item.item_type)?,
             item_id: ItemID::from(serde_item.item_id),
-            color: serde_item.color.map(|c| Color::from(c)),
-            max_price: serde_item.max_price.map(|m| MaxPrice::from(m)),
-            min_qty: serde_item.min_qty.map(|m| MinQty::from(m)),
-            qty_filled: serde_item.qty_filled.map(|q| QtyFilled::from(q)),
-            condition: serde_item.condition.map(|c| Condition::from(c)),
-            remarks: serde_item.remarks.map(|r| Remarks::from(r)),
-            notify: serde_item.notify.map(|n| Notify::from(n)),
-            wanted_show: serde_item.wanted_show.map(|w| WantedShow::from(w)),
-            wanted_list_id: serde_item.wanted_list_id.map(|w| WantedListID::from(w)),
-        }
+            color: serde_item.color.map(Color::from),
+            max_price: serde_item.max_price.map(MaxPrice::try_from).transpose()?,
+            min_qty: serde_item.min_qty.map(MinQty::parse).transpose()?,
+            qty_filled: serde_item.qty_filled.map(QtyFilled::from),
+            condition: serde_item.condition.map(Condition::try_from).transpose()?,
+            remarks: serde_item.remarks.map(Remarks::from),
+            notify: flag("NOTIFY", serde_item.notify)?,
+            wanted_show: serde_item
+                .wanted_show
+                .map(WantedShow::try_from)
+                .transpose()?,
+            wanted_list_id: serde_item.wanted_list_id.map(WantedListID::from),
+            alternate: flag("ALTERNATE", serde_item.alternate)?,
+            counterpart: flag("COUNTERPART", serde_item.counterpart)?,
+            extra: serde_item.extra,
+        })
     }
 }
 
@@ -300,20 +2003,244 @@ impl std::convert::From<Item> for SerdeItem {
         SerdeItem {
             item_type: String::from(item.item_type),
             item_id: String::from(item.item_id),
-            color: item.color.map(|c| i8::from(c)),
-            max_price: item.max_price.map(|m| String::from(m)),
-            min_qty: item.min_qty.map(|m| i32::from(m)),
-            qty_filled: item.qty_filled.map(|q| i32::from(q)),
-            condition: item.condition.map(|c| String::from(c)),
-            remarks: item.remarks.map(|r| String::from(r)),
-            notify: item.notify.map(|n| String::from(n)),
-            wanted_show: item.wanted_show.map(|ws| String::from(ws)),
-            wanted_list_id: item.wanted_list_id.map(|w| String::from(w)),
+            color: item.color.map(i32::from),
+            max_price: item.max_price.map(String::from),
+            min_qty: item.min_qty.map(i32::from),
+            qty_filled: item.qty_filled.map(i32::from),
+            condition: item.condition.map(String::from),
+            remarks: item.remarks.map(String::from),
+            notify: item.notify.map(String::from),
+            wanted_show: item.wanted_show.map(String::from),
+            wanted_list_id: item.wanted_list_id.map(String::from),
+            alternate: item.alternate.map(String::from),
+            counterpart: item.counterpart.map(String::from),
+            extra: item.extra,
         }
     }
 }
 
 impl Item {
+    /// The canonical ordering of Items: by ItemType, then ItemID, then Color.
+    /// ItemIDs compare as strings, and an Item with no Color sorts before
+    /// the same ItemID in any Color.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Item to compare against
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use brickline::wanted::{Item, ItemType, ItemID, Color};
+    ///
+    /// let colorless = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, None);
+    /// let colored = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), None);
+    /// assert_eq!(colorless.canonical_cmp(&colored), Ordering::Less);
+    /// ```
+    pub fn canonical_cmp(&self, other: &Item) -> Ordering {
+        self.item_type
+            .cmp(&other.item_type)
+            .then_with(|| self.item_id.cmp(&other.item_id))
+            .then_with(|| self.color.cmp(&other.color))
+    }
+
+    /// How many more of this Item are needed: MinQty minus QtyFilled,
+    /// floored at zero. A missing MinQty counts as 1 and a missing
+    /// QtyFilled as 0. A negative QtyFilled also counts as 0, so nothing is
+    /// ever more needed than its MinQty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemType, ItemID, MinQty, QtyFilled};
+    ///
+    /// let mut test_item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3001")),
+    ///     None,
+    ///     Some(MinQty(100))
+    /// );
+    /// test_item.qty_filled = Some(QtyFilled(40));
+    /// assert_eq!(test_item.remaining_qty(), 60);
+    /// ```
+    pub fn remaining_qty(&self) -> i32 {
+        let min_qty = self.min_qty.as_ref().map_or(1, |min_qty| min_qty.0);
+        let qty_filled = self
+            .qty_filled
+            .as_ref()
+            .map_or(0, |qty_filled| qty_filled.0.max(0));
+        min_qty.saturating_sub(qty_filled).max(0)
+    }
+
+    /// Is this Item a Part?
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemType, ItemID};
+    ///
+    /// let test_item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, None);
+    /// assert!(test_item.is_part());
+    /// assert!(!test_item.is_minifig());
+    /// ```
+    pub fn is_part(&self) -> bool {
+        self.item_type == ItemType::Part
+    }
+
+    /// Is this Item a Minifig?
+    pub fn is_minifig(&self) -> bool {
+        self.item_type == ItemType::Minifig
+    }
+
+    /// Is this Item a Set?
+    pub fn is_set(&self) -> bool {
+        self.item_type == ItemType::Set
+    }
+
+    /// Is this Item Gear?
+    pub fn is_gear(&self) -> bool {
+        self.item_type == ItemType::Gear
+    }
+
+    /// Check the Item for problems that would stop Bricklink from accepting
+    /// it before adding it to a list: an empty ItemID, a Color that isn't in
+    /// the Bricklink color catalog, a MinQty that isn't positive, or a
+    /// QtyFilled larger than the MinQty. Every problem is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemProblem, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), Some(MinQty(2)));
+    /// assert!(item.validate().is_ok());
+    ///
+    /// let item = Item::build_test_item(ItemType::Part, ItemID(String::new()), Some(Color(9999)), None);
+    /// assert_eq!(
+    ///     item.validate(),
+    ///     Err(vec![ItemProblem::EmptyItemId, ItemProblem::UnknownColor(9999)])
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ItemProblem>> {
+        let problems = SerdeItem::from(self.clone()).problems();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Fill in the fields this Item is missing from another Item, e.g. when
+    /// joining lists where the righthand one knows the Condition. The rules,
+    /// field by field:
+    ///
+    /// * ItemType, ItemID, and Color identify the Item and are never changed
+    /// * MinQty and QtyFilled are left alone; combining quantities is up to
+    ///   the join strategy
+    /// * MaxPrice, Condition, Remarks, Notify, WantedShow, WantedListID,
+    ///   Alternate, and Counterpart are taken from `other` only when this
+    ///   Item has none, so a value that is already set always wins
+    /// * Unmodelled elements are added from `other` when this Item doesn't
+    ///   have an element of the same name
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: Item to take missing fields from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemType, ItemID, Condition};
+    ///
+    /// let mut item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), None, None);
+    /// let mut other = item.clone();
+    /// other.condition = Some(Condition::Used);
+    /// item.merge_fields(&other);
+    /// assert_eq!(item.condition, Some(Condition::Used));
+    /// ```
+    pub fn merge_fields(&mut self, other: &Item) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                *field = other.clone();
+            }
+        }
+        fill(&mut self.max_price, &other.max_price);
+        fill(&mut self.condition, &other.condition);
+        fill(&mut self.remarks, &other.remarks);
+        fill(&mut self.notify, &other.notify);
+        fill(&mut self.wanted_show, &other.wanted_show);
+        fill(&mut self.wanted_list_id, &other.wanted_list_id);
+        fill(&mut self.alternate, &other.alternate);
+        fill(&mut self.counterpart, &other.counterpart);
+        for (name, value) in other.extra.iter() {
+            self.extra
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+
+    /// The owned ItemID/Color key of the Item, which joins and statistics
+    /// use to decide whether two Items are the same lot. Build keys with
+    /// this rather than by hand so the call sites can't drift apart.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemType, ItemID, Color};
+    ///
+    /// let item = Item::build_test_item(ItemType::Part, ItemID(String::from("3001")), Some(Color(5)), None);
+    /// assert_eq!(item.color_key(), (ItemID(String::from("3001")), Some(Color(5))));
+    /// ```
+    pub fn color_key(&self) -> (ItemID, Option<Color>) {
+        (self.item_id.clone(), self.color.clone())
+    }
+
+    /// Serialize the Item as a JSON object for API payloads, with readable
+    /// field names (`item_id`, `min_qty`, ...) and unset fields left out.
+    /// Values keep their Bricklink codes, e.g. `"item_type": "P"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemType, ItemID, Color, MinQty};
+    ///
+    /// let item = Item::build_test_item(
+    ///     ItemType::Part,
+    ///     ItemID(String::from("3001")),
+    ///     Some(Color(5)),
+    ///     Some(MinQty(4))
+    /// );
+    /// assert_eq!(
+    ///     item.to_json().unwrap(),
+    ///     r#"{"item_type":"P","item_id":"3001","color":5,"min_qty":4}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&JsonItem::from(SerdeItem::from(self.clone())))
+    }
+
+    /// Deserialize an Item from the JSON written by `to_json`. Only
+    /// `item_type` and `item_id` are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `json`: JSON object of the Item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::{Item, ItemID, MinQty};
+    ///
+    /// let item = Item::from_json(r#"{"item_type":"P","item_id":"3001","min_qty":4}"#).unwrap();
+    /// assert_eq!(item.item_id, ItemID(String::from("3001")));
+    /// assert_eq!(item.min_qty, Some(MinQty(4)));
+    /// ```
+    pub fn from_json(json: &str) -> Result<Item, serde_json::Error> {
+        let json_item = serde_json::from_str::<JsonItem>(json)?;
+        Item::try_from(SerdeItem::from(json_item)).map_err(serde::de::Error::custom)
+    }
+
     /// Build a test Item with item_type, item_id, color, and all other fields set to
     /// None. Only used as a test data generator.
     ///
@@ -341,24 +2268,194 @@ impl Item {
         color: Option<Color>,
         min_qty: Option<MinQty>,
     ) -> Item {
-        Item {
-            item_type,
-            item_id: item_id.into(),
-            color: color.map(|c| c.into()),
-            min_qty: min_qty.map(|m| m.into()),
-            max_price: None,
-            qty_filled: None,
-            condition: None,
-            remarks: None,
-            notify: None,
-            wanted_show: None,
-            wanted_list_id: None,
+        ItemBuilder {
+            item_type: Some(item_type),
+            item_id: Some(item_id),
+            color,
+            min_qty,
+            ..ItemBuilder::default()
         }
+        .build()
+        .expect("item_type and item_id are always set")
     }
 }
 
-/// The type of the Lego Item
-#[derive(Clone, Debug, PartialEq)]
+/// A concise one-line form of an Item for debugging, e.g.
+/// `P 3001 color=5 qty=100`. Only the fields that are set are shown, using
+/// their Bricklink codes.
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            String::from(self.item_type.clone()),
+            self.item_id.0
+        )?;
+        if let Some(color) = &self.color {
+            write!(f, " color={}", color.0)?;
+        }
+        if let Some(min_qty) = &self.min_qty {
+            write!(f, " qty={}", min_qty.0)?;
+        }
+        if let Some(qty_filled) = &self.qty_filled {
+            write!(f, " filled={}", qty_filled.0)?;
+        }
+        if let Some(max_price) = &self.max_price {
+            write!(f, " price={}", String::from(max_price.clone()))?;
+        }
+        if let Some(condition) = &self.condition {
+            write!(f, " condition={}", String::from(condition.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+/// An ItemBuilder was built without one of the fields every Item needs
+#[derive(Debug, PartialEq)]
+pub struct MissingFieldError {
+    pub field: &'static str,
+}
+
+impl std::fmt::Display for MissingFieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Item is missing required field {}", self.field)
+    }
+}
+
+impl std::error::Error for MissingFieldError {}
+
+/// Builds an Item one field at a time. ItemType and ItemID are required;
+/// every other field is left as None unless set.
+///
+/// # Example
+///
+/// ```
+/// use brickline::wanted::{ItemBuilder, ItemType, ItemID, Color, Condition, Remarks};
+///
+/// let item = ItemBuilder::new()
+///     .item_type(ItemType::Part)
+///     .item_id(ItemID(String::from("3001")))
+///     .color(Color(5))
+///     .condition(Condition::Used)
+///     .remarks(Remarks(String::from("for MOC AB154A")))
+///     .build()
+///     .unwrap();
+/// assert_eq!(item.condition, Some(Condition::Used));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ItemBuilder {
+    item_type: Option<ItemType>,
+    item_id: Option<ItemID>,
+    color: Option<Color>,
+    max_price: Option<MaxPrice>,
+    min_qty: Option<MinQty>,
+    qty_filled: Option<QtyFilled>,
+    condition: Option<Condition>,
+    remarks: Option<Remarks>,
+    notify: Option<Notify>,
+    wanted_show: Option<WantedShow>,
+    wanted_list_id: Option<WantedListID>,
+    alternate: Option<Notify>,
+    counterpart: Option<Notify>,
+}
+
+impl ItemBuilder {
+    pub fn new() -> ItemBuilder {
+        ItemBuilder::default()
+    }
+
+    pub fn item_type(mut self, item_type: ItemType) -> ItemBuilder {
+        self.item_type = Some(item_type);
+        self
+    }
+
+    /// Takes an ItemID, or anything that converts into one, e.g. `"3001"`
+    pub fn item_id(mut self, item_id: impl Into<ItemID>) -> ItemBuilder {
+        self.item_id = Some(item_id.into());
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> ItemBuilder {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn max_price(mut self, max_price: MaxPrice) -> ItemBuilder {
+        self.max_price = Some(max_price);
+        self
+    }
+
+    pub fn min_qty(mut self, min_qty: MinQty) -> ItemBuilder {
+        self.min_qty = Some(min_qty);
+        self
+    }
+
+    pub fn qty_filled(mut self, qty_filled: QtyFilled) -> ItemBuilder {
+        self.qty_filled = Some(qty_filled);
+        self
+    }
+
+    pub fn condition(mut self, condition: Condition) -> ItemBuilder {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Takes Remarks, or anything that converts into them, e.g. `"for MOC"`
+    pub fn remarks(mut self, remarks: impl Into<Remarks>) -> ItemBuilder {
+        self.remarks = Some(remarks.into());
+        self
+    }
+
+    pub fn notify(mut self, notify: Notify) -> ItemBuilder {
+        self.notify = Some(notify);
+        self
+    }
+
+    pub fn wanted_show(mut self, wanted_show: WantedShow) -> ItemBuilder {
+        self.wanted_show = Some(wanted_show);
+        self
+    }
+
+    pub fn wanted_list_id(mut self, wanted_list_id: WantedListID) -> ItemBuilder {
+        self.wanted_list_id = Some(wanted_list_id);
+        self
+    }
+
+    pub fn alternate(mut self, alternate: Notify) -> ItemBuilder {
+        self.alternate = Some(alternate);
+        self
+    }
+
+    pub fn counterpart(mut self, counterpart: Notify) -> ItemBuilder {
+        self.counterpart = Some(counterpart);
+        self
+    }
+
+    /// Build the Item, failing if ItemType or ItemID was never set
+    pub fn build(self) -> Result<Item, MissingFieldError> {
+        Ok(Item {
+            item_type: self
+                .item_type
+                .ok_or(MissingFieldError { field: "ITEMTYPE" })?,
+            item_id: self.item_id.ok_or(MissingFieldError { field: "ITEMID" })?,
+            color: self.color,
+            max_price: self.max_price,
+            min_qty: self.min_qty,
+            qty_filled: self.qty_filled,
+            condition: self.condition,
+            remarks: self.remarks,
+            notify: self.notify,
+            wanted_show: self.wanted_show,
+            wanted_list_id: self.wanted_list_id,
+            alternate: self.alternate,
+            counterpart: self.counterpart,
+            extra: BTreeMap::new(),
+        })
+    }
+}
+
+/// The type of the Lego Item. Ordering follows declaration order.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize)]
 pub enum ItemType {
     Set,
     Part,
@@ -371,23 +2468,50 @@ pub enum ItemType {
     UnsortedLot,
 }
 
-impl std::convert::From<String> for ItemType {
-    fn from(itemtype_str: String) -> ItemType {
-        match itemtype_str.as_str() {
-            "S" => Self::Set,
-            "P" => Self::Part,
-            "M" => Self::Minifig,
-            "B" => Self::Book,
-            "G" => Self::Gear,
-            "C" => Self::Catalog,
-            "I" => Self::Instruction,
-            "O" => Self::OriginalBox,
-            "U" => Self::UnsortedLot,
-            unsupported => panic!(format!("{} is not a supported ItemType!", unsupported)),
+impl ItemType {
+    /// Look up the ItemType for a Bricklink item type code, e.g. "P" for
+    /// Part. Codes are trimmed and uppercased first, so " p " is also Part.
+    /// Returns None for unsupported codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Bricklink item type code
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::ItemType;
+    ///
+    /// assert_eq!(ItemType::from_code("P"), Some(ItemType::Part));
+    /// assert_eq!(ItemType::from_code("Z"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<ItemType> {
+        match code.trim().to_uppercase().as_str() {
+            "S" => Some(Self::Set),
+            "P" => Some(Self::Part),
+            "M" => Some(Self::Minifig),
+            "B" => Some(Self::Book),
+            "G" => Some(Self::Gear),
+            "C" => Some(Self::Catalog),
+            "I" => Some(Self::Instruction),
+            "O" => Some(Self::OriginalBox),
+            "U" => Some(Self::UnsortedLot),
+            _ => None,
         }
     }
 }
 
+impl std::convert::TryFrom<String> for ItemType {
+    type Error = ParseError;
+
+    fn try_from(itemtype_str: String) -> Result<Self, Self::Error> {
+        ItemType::from_code(&itemtype_str).ok_or(ParseError {
+            field: "ITEMTYPE",
+            value: itemtype_str,
+        })
+    }
+}
+
 impl std::convert::From<ItemType> for String {
     fn from(item_type: ItemType) -> String {
         match item_type {
@@ -408,12 +2532,75 @@ impl std::convert::From<ItemType> for String {
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct ItemID(pub String);
 
+/// Suffixes stripped by `ItemID::normalized`, in the order they're tried.
+/// Bricklink records some older molds of a part under suffixed IDs, like
+/// `3001old`. Letter suffixes are left out on purpose: `3062a` and `3062b`
+/// are different molds, and minifig IDs like `sw0001a` are different figures,
+/// so stripping them has to be asked for with `normalized_with`.
+pub const DEFAULT_ID_SUFFIXES: [&str; 1] = ["old"];
+
+impl ItemID {
+    /// Normalize the ItemID by stripping one of the `DEFAULT_ID_SUFFIXES`,
+    /// so that variants of the same part can be merged. See
+    /// `normalized_with` for the rules.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::ItemID;
+    ///
+    /// assert_eq!(ItemID(String::from("3001old")).normalized(), ItemID(String::from("3001")));
+    /// assert_eq!(ItemID(String::from("3062a")).normalized(), ItemID(String::from("3062a")));
+    /// assert_eq!(ItemID(String::from("6020-1")).normalized(), ItemID(String::from("6020-1")));
+    /// ```
+    pub fn normalized(&self) -> ItemID {
+        self.normalized_with(&DEFAULT_ID_SUFFIXES)
+    }
+
+    /// Normalize the ItemID by stripping a suffix. The rules are:
+    ///
+    /// 1. Suffixes are tried in order, and only the first one the ItemID
+    ///    ends with is stripped. Matching ignores ASCII case.
+    /// 2. A suffix is only stripped if what's left is non-empty and ends in
+    ///    a digit, so with `a` as a suffix `3001a` becomes `3001` but `bb`
+    ///    is left alone.
+    /// 3. Otherwise the ItemID is returned unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `suffixes`: Suffixes to strip, in order of preference
+    ///
+    pub fn normalized_with<S: AsRef<str>>(&self, suffixes: &[S]) -> ItemID {
+        let lower = self.0.to_ascii_lowercase();
+        for suffix in suffixes.iter() {
+            let suffix = suffix.as_ref().to_ascii_lowercase();
+            if suffix.is_empty() || !lower.ends_with(&suffix) {
+                continue;
+            }
+            // Lowercasing ASCII doesn't change byte lengths, so this is a
+            // char boundary of the original too
+            let stem = &self.0[..self.0.len() - suffix.len()];
+            if stem.ends_with(|c: char| c.is_ascii_digit()) {
+                return ItemID(String::from(stem));
+            }
+            return self.clone();
+        }
+        self.clone()
+    }
+}
+
 impl std::convert::From<String> for ItemID {
     fn from(input_str: String) -> ItemID {
         Self(input_str)
     }
 }
 
+impl std::convert::From<&str> for ItemID {
+    fn from(input_str: &str) -> ItemID {
+        Self(String::from(input_str))
+    }
+}
+
 impl std::convert::From<ItemID> for String {
     fn from(item_id: ItemID) -> String {
         item_id.0
@@ -423,36 +2610,164 @@ impl std::convert::From<ItemID> for String {
 /// Color ID according to the Bricklink color catalog
 /// https://www.bricklink.com/catalogColors.asp
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
-pub struct Color(pub i8);
+pub struct Color(pub i32);
+
+impl Color {
+    /// Is this a color ID in the Bricklink color catalog?
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::Color;
+    ///
+    /// assert!(Color(11).is_valid());
+    /// assert!(!Color(9999).is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        BRICKLINK_COLOR_IDS.binary_search(&self.0).is_ok()
+    }
+
+    /// The Bricklink catalog name of this color, or None if the color ID
+    /// isn't in the catalog
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::Color;
+    ///
+    /// assert_eq!(Color(12).name(), Some("Trans-Clear"));
+    /// assert_eq!(Color(9999).name(), None);
+    /// ```
+    pub fn name(&self) -> Option<&'static str> {
+        BRICKLINK_COLORS
+            .binary_search_by_key(&self.0, |&(id, _)| id)
+            .ok()
+            .map(|position| BRICKLINK_COLORS[position].1)
+    }
+}
 
-impl std::convert::From<i8> for Color {
-    fn from(input_i8: i8) -> Color {
-        Self(input_i8)
+impl std::convert::From<i32> for Color {
+    fn from(input_i32: i32) -> Color {
+        Self(input_i32)
     }
 }
 
-impl std::convert::From<Color> for i8 {
-    fn from(color: Color) -> i8 {
+impl std::convert::From<Color> for i32 {
+    fn from(color: Color) -> i32 {
         color.0
     }
 }
 
-/// Maximum Desired Price
+/// Maximum Desired Price, stored as a whole number of cents so that
+/// prices don't drift the way binary floating point would. Bricklink uses
+/// -1.00 to mean "no maximum", so this is signed.
 #[derive(Clone, Debug, PartialEq)]
-pub struct MaxPrice(pub f32);
+pub struct MaxPrice(pub i64);
+
+impl MaxPrice {
+    /// Parse a decimal price string like "1.00" or "-1.00" into cents.
+    /// Digits past the second decimal place are rounded half away from zero,
+    /// so "1.005" is 101 cents and "-1.005" is -101 cents.
+    ///
+    /// Locale-exported lists sometimes use a comma as the decimal separator,
+    /// so a lone comma with no dot and one or two digits after it, like
+    /// "1,00", is read as "1.00". Any other comma, like the thousands
+    /// separator in "1,000", is ambiguous and rejected.
+    /// Scientific notation like "1e2" is rejected rather than guessed at.
+    ///
+    /// # Arguments
+    ///
+    /// * `price_str`: Decimal price string
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::MaxPrice;
+    ///
+    /// assert_eq!(MaxPrice::parse_cents("1234.99"), Some(123499));
+    /// assert_eq!(MaxPrice::parse_cents("1,50"), Some(150));
+    /// assert_eq!(MaxPrice::parse_cents("1,000"), None);
+    /// assert_eq!(MaxPrice::parse_cents("1e2"), None);
+    /// assert_eq!(MaxPrice::parse_cents("abc"), None);
+    /// ```
+    pub fn parse_cents(price_str: &str) -> Option<i64> {
+        if price_str.contains(['e', 'E']) {
+            return None;
+        }
+        let normalized;
+        let price_str = match price_str.find(',') {
+            Some(idx) => {
+                let decimals = price_str.len() - idx - 1;
+                if price_str.matches(',').count() > 1
+                    || price_str.contains('.')
+                    || !(1..=2).contains(&decimals)
+                {
+                    return None;
+                }
+                normalized = price_str.replace(',', ".");
+                normalized.as_str()
+            }
+            None => price_str,
+        };
+        let (negative, unsigned) = match price_str.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, price_str),
+        };
+        let (whole, fraction) = match unsigned.find('.') {
+            Some(idx) => (&unsigned[..idx], &unsigned[idx + 1..]),
+            None => (unsigned, ""),
+        };
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction)
+        {
+            return None;
+        }
 
-impl std::convert::From<String> for MaxPrice {
-    fn from(input_string: String) -> MaxPrice {
-        match input_string.parse::<f32>() {
-            Ok(max_price) => return Self(max_price),
-            Err(_e) => panic!("Could not parse MaxPrice {}", input_string),
+        let whole_cents = match whole {
+            "" => 0,
+            digits => digits.parse::<i64>().ok()?.checked_mul(100)?,
         };
+        let mut fraction_digits = fraction.bytes().map(|b| i64::from(b - b'0'));
+        let tenths = fraction_digits.next().unwrap_or(0);
+        let hundredths = fraction_digits.next().unwrap_or(0);
+        let round_up = fraction_digits
+            .next()
+            .map_or(0, |d| if d >= 5 { 1 } else { 0 });
+        let cents = whole_cents.checked_add(tenths * 10 + hundredths + round_up)?;
+
+        Some(if negative { -cents } else { cents })
+    }
+}
+
+impl std::convert::TryFrom<String> for MaxPrice {
+    type Error = ParseError;
+
+    fn try_from(input_string: String) -> Result<Self, Self::Error> {
+        match MaxPrice::parse_cents(&input_string) {
+            Some(cents) => Ok(Self(cents)),
+            None => Err(ParseError {
+                field: "MAXPRICE",
+                value: input_string,
+            }),
+        }
     }
 }
 
 impl std::convert::From<MaxPrice> for String {
     fn from(max_price: MaxPrice) -> String {
-        format!("{:.2}", max_price.0).to_string()
+        let sign = if max_price.0 < 0 { "-" } else { "" };
+        let cents = max_price.0.unsigned_abs();
+        format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+/// MaxPrice serializes to the same decimal string as in the XML, e.g. "1.50"
+impl Serialize for MaxPrice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&String::from(self.clone()))
     }
 }
 
@@ -460,6 +2775,35 @@ impl std::convert::From<MaxPrice> for String {
 #[derive(Clone, Debug, PartialEq)]
 pub struct MinQty(pub i32);
 
+impl MinQty {
+    /// Check a MinQty read from a wanted list. Bricklink only accepts a
+    /// MinQty of at least 1, and 0 or negative quantities would throw off
+    /// the join arithmetic, so those are a ParseError.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_qty`: MinQty as read from the XML
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use brickline::wanted::MinQty;
+    ///
+    /// assert_eq!(MinQty::parse(4), Ok(MinQty(4)));
+    /// assert!(MinQty::parse(0).is_err());
+    /// ```
+    pub fn parse(min_qty: i32) -> Result<MinQty, ParseError> {
+        if min_qty >= 1 {
+            Ok(MinQty(min_qty))
+        } else {
+            Err(ParseError {
+                field: "MINQTY",
+                value: min_qty.to_string(),
+            })
+        }
+    }
+}
+
 impl std::convert::From<i32> for MinQty {
     fn from(input_i32: i32) -> MinQty {
         Self(input_i32)
@@ -488,8 +2832,8 @@ impl std::convert::From<QtyFilled> for i32 {
     }
 }
 
-/// Item condition
-#[derive(Clone, Debug, PartialEq)]
+/// Item condition. Ordering follows declaration order.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum Condition {
     New,
     Used,
@@ -499,16 +2843,21 @@ pub enum Condition {
     NotProvided,
 }
 
-impl std::convert::From<String> for Condition {
-    fn from(condition_str: String) -> Condition {
-        match condition_str.as_str() {
-            "N" => Self::New,
-            "U" => Self::Used,
-            "C" => Self::Complete,
-            "I" => Self::Incomplete,
-            "S" => Self::Sealed,
-            "X" => Self::NotProvided,
-            unsupported => panic!(format!("{} is not a supported Condition!", unsupported)),
+impl std::convert::TryFrom<String> for Condition {
+    type Error = ParseError;
+
+    fn try_from(condition_str: String) -> Result<Self, Self::Error> {
+        match condition_str.trim().to_uppercase().as_str() {
+            "N" => Ok(Self::New),
+            "U" => Ok(Self::Used),
+            "C" => Ok(Self::Complete),
+            "I" => Ok(Self::Incomplete),
+            "S" => Ok(Self::Sealed),
+            "X" => Ok(Self::NotProvided),
+            _ => Err(ParseError {
+                field: "CONDITION",
+                value: condition_str,
+            }),
         }
     }
 }
@@ -536,25 +2885,37 @@ impl std::convert::From<String> for Remarks {
     }
 }
 
+impl std::convert::From<&str> for Remarks {
+    fn from(input_str: &str) -> Remarks {
+        Self(String::from(input_str))
+    }
+}
+
 impl std::convert::From<Remarks> for String {
     fn from(remarks: Remarks) -> String {
         remarks.0
     }
 }
 
-/// Be notified when these items are listed for sale
+/// Be notified when these items are listed for sale. Also used for the
+/// other Y/N flags, ALTERNATE and COUNTERPART.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Notify {
     Y,
     N,
 }
 
-impl std::convert::From<String> for Notify {
-    fn from(notify_str: String) -> Notify {
-        match notify_str.as_str() {
-            "Y" => Self::Y,
-            "N" => Self::N,
-            unsupported => panic!(format!("{} is not a supported Notify!", unsupported)),
+impl std::convert::TryFrom<String> for Notify {
+    type Error = ParseError;
+
+    fn try_from(notify_str: String) -> Result<Self, Self::Error> {
+        match notify_str.trim().to_uppercase().as_str() {
+            "Y" => Ok(Self::Y),
+            "N" => Ok(Self::N),
+            _ => Err(ParseError {
+                field: "NOTIFY",
+                value: notify_str,
+            }),
         }
     }
 }
@@ -575,12 +2936,17 @@ pub enum WantedShow {
     N,
 }
 
-impl std::convert::From<String> for WantedShow {
-    fn from(wantedshow_str: String) -> WantedShow {
-        match wantedshow_str.as_str() {
-            "Y" => Self::Y,
-            "N" => Self::N,
-            unsupported => panic!(format!("{} is not a supported WantedShow!", unsupported)),
+impl std::convert::TryFrom<String> for WantedShow {
+    type Error = ParseError;
+
+    fn try_from(wantedshow_str: String) -> Result<Self, Self::Error> {
+        match wantedshow_str.trim().to_uppercase().as_str() {
+            "Y" => Ok(Self::Y),
+            "N" => Ok(Self::N),
+            _ => Err(ParseError {
+                field: "WANTEDSHOW",
+                value: wantedshow_str,
+            }),
         }
     }
 }
@@ -0,0 +1,369 @@
+extern crate brickline;
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// A path in the temp directory that is unique to this test run
+    fn temp_path(file_name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("brickline_{}_{}", std::process::id(), file_name));
+        path
+    }
+
+    /// Run the brickline binary with the given arguments, answering yes to
+    /// any overwrite prompts
+    fn run_brickline(args: &[&str]) -> Output {
+        let output = Command::new(env!("CARGO_BIN_EXE_brickline"))
+            .arg("-y")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "brickline failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    }
+
+    #[test]
+    fn test_join_summary() {
+        let left_path = common::get_resource_path("test_wanted_list_1.xml");
+        let right_path = common::get_resource_path("test_wanted_list_2.xml");
+        let out_path = temp_path("joined_summary.xml");
+        let output = run_brickline(&[
+            "join",
+            "-l",
+            left_path.to_str().unwrap(),
+            "-r",
+            right_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+            "--summary",
+        ]);
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let summary = stdout
+            .split("Joined Wanted List Summary")
+            .nth(1)
+            .expect("No summary printed");
+        let total_items = summary
+            .lines()
+            .find_map(|line| line.strip_prefix("Total Items: "))
+            .map(|count| count.trim_end_matches(", ").parse::<usize>().unwrap())
+            .expect("No total item count in summary");
+
+        let (joined, _) = brickline::file_to_wanted_list(out_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+        assert_eq!(total_items, joined.items.len());
+    }
+
+    #[test]
+    fn test_join_dir_empty_directory() {
+        let dir_path = temp_path("empty_dir");
+        std::fs::create_dir_all(&dir_path).unwrap();
+        let out_path = temp_path("empty_dir_joined.xml");
+        let output = Command::new(env!("CARGO_BIN_EXE_brickline"))
+            .args([
+                "-y",
+                "join-dir",
+                "-d",
+                dir_path.to_str().unwrap(),
+                "-o",
+                out_path.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+        std::fs::remove_dir(&dir_path).unwrap();
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("No .xml wanted lists found in"));
+        assert!(!out_path.exists());
+    }
+
+    #[test]
+    fn test_count() {
+        let input_path = common::get_resource_path("bricklink_example.xml");
+        let input = input_path.to_str().unwrap();
+        let count_stdout = |flags: &[&str]| {
+            let mut args = vec!["count", "-i", input];
+            args.extend_from_slice(flags);
+            String::from_utf8(run_brickline(&args).stdout).unwrap()
+        };
+
+        assert_eq!(count_stdout(&[]), "3 102\n");
+        assert_eq!(count_stdout(&["--lots-only"]), "3\n");
+        assert_eq!(count_stdout(&["--parts-only"]), "102\n");
+    }
+
+    #[test]
+    fn test_show() {
+        let input_path = common::get_resource_path("test_wanted_list_1.xml");
+        let input = input_path.to_str().unwrap();
+
+        let output = run_brickline(&["show", "-i", input, "--id", "3001", "--color", "5"]);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "P 3001 color=5 qty=100\n"
+        );
+        let output = run_brickline(&["show", "-i", input, "--id", "3623"]);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "P 3623 color=11 filled=4\n"
+        );
+
+        let output = Command::new(env!("CARGO_BIN_EXE_brickline"))
+            .args(["show", "-i", input, "--id", "3001", "--color", "11"])
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("is not in"));
+    }
+
+    #[test]
+    fn test_join_exclude() {
+        let left_path = common::get_resource_path("test_wanted_list_1.xml");
+        let right_path = common::get_resource_path("test_wanted_list_2.xml");
+        let exclude_path = temp_path("exclude.txt");
+        std::fs::write(&exclude_path, "3001\n").unwrap();
+        let out_path = temp_path("joined_exclude.xml");
+        run_brickline(&[
+            "join",
+            "-l",
+            left_path.to_str().unwrap(),
+            "-r",
+            right_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+            "--exclude",
+            exclude_path.to_str().unwrap(),
+        ]);
+
+        let (joined, _) = brickline::file_to_wanted_list(out_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&exclude_path).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+        assert!(!joined.items.is_empty());
+        assert!(joined.items.iter().all(|item| item.item_id.0 != "3001"));
+    }
+
+    #[test]
+    fn test_merge_three_inputs() {
+        let input_paths: Vec<PathBuf> = vec![
+            "test_wanted_list_1.xml",
+            "test_wanted_list_2.xml",
+            "test_wanted_list_3.xml",
+        ]
+        .into_iter()
+        .map(common::get_resource_path)
+        .collect();
+        let out_path = temp_path("merged_three.xml");
+        let mut args = vec!["merge"];
+        for input_path in input_paths.iter() {
+            args.push("-i");
+            args.push(input_path.to_str().unwrap());
+        }
+        args.push("-o");
+        args.push(out_path.to_str().unwrap());
+        run_brickline(&args);
+
+        let wanted_lists: Vec<brickline::wanted::WantedList> = input_paths
+            .iter()
+            .map(|path| {
+                brickline::file_to_wanted_list(path.to_str().unwrap())
+                    .unwrap()
+                    .0
+            })
+            .collect();
+        let (merged, _) = brickline::file_to_wanted_list(out_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+        assert_eq!(merged, brickline::join_many(&wanted_lists));
+    }
+
+    #[test]
+    fn test_merge_requires_two_inputs() {
+        let input_path = common::get_resource_path("test_wanted_list_1.xml");
+        let out_path = temp_path("merged_one.xml");
+        let output = Command::new(env!("CARGO_BIN_EXE_brickline"))
+            .args([
+                "-y",
+                "merge",
+                "-i",
+                input_path.to_str().unwrap(),
+                "-o",
+                out_path.to_str().unwrap(),
+            ])
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(!out_path.exists());
+    }
+
+    #[test]
+    fn test_normalize_idempotent() {
+        let input_path = common::get_resource_path("test_wanted_list_4.xml");
+        let once_path = temp_path("normalized_once.xml");
+        let twice_path = temp_path("normalized_twice.xml");
+        run_brickline(&[
+            "normalize",
+            "-i",
+            input_path.to_str().unwrap(),
+            "-o",
+            once_path.to_str().unwrap(),
+        ]);
+        run_brickline(&[
+            "normalize",
+            "-i",
+            once_path.to_str().unwrap(),
+            "-o",
+            twice_path.to_str().unwrap(),
+        ]);
+
+        let once = std::fs::read(&once_path).unwrap();
+        let twice = std::fs::read(&twice_path).unwrap();
+        std::fs::remove_file(&once_path).unwrap();
+        std::fs::remove_file(&twice_path).unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_sort_pretty_crlf() {
+        let input_path = common::get_resource_path("test_wanted_list_4.xml");
+        let out_path = temp_path("sorted_crlf.xml");
+        run_brickline(&[
+            "sort",
+            "-i",
+            input_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+            "--pretty",
+            "--crlf",
+        ]);
+
+        let written = std::fs::read_to_string(&out_path).unwrap();
+        let (reparsed, _) = brickline::file_to_wanted_list(out_path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+        assert!(written.contains("\r\n"));
+        assert!(!written.replace("\r\n", "").contains('\n'));
+
+        let (mut expected, _) =
+            brickline::file_to_wanted_list(input_path.to_str().unwrap()).unwrap();
+        expected.sort();
+        assert_eq!(reparsed, expected);
+    }
+
+    #[test]
+    fn test_sort_indent_four() {
+        let input_path = common::get_resource_path("test_wanted_list_4.xml");
+        let out_path = temp_path("sorted_indent_four.xml");
+        run_brickline(&[
+            "sort",
+            "-i",
+            input_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+            "--pretty",
+            "--indent",
+            "4",
+        ]);
+
+        let written = std::fs::read_to_string(&out_path).unwrap();
+        std::fs::remove_file(&out_path).unwrap();
+        assert!(written.contains("\n    <ITEM>\n        <ITEMTYPE>"));
+    }
+
+    #[test]
+    fn test_split_output_dir_and_prefix() {
+        let input_path = common::get_resource_path("test_wanted_list_4.xml");
+        let out_dir = temp_path("split_dir");
+        let split_args = [
+            "split",
+            "-i",
+            input_path.to_str().unwrap(),
+            "-n",
+            "100",
+            "--output-dir",
+            out_dir.to_str().unwrap(),
+            "--prefix",
+            "batch",
+        ];
+
+        // The directory isn't created without --mkdir
+        let output = Command::new(env!("CARGO_BIN_EXE_brickline"))
+            .arg("-y")
+            .args(split_args.iter())
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(!out_dir.exists());
+
+        let mut mkdir_args = split_args.to_vec();
+        mkdir_args.push("--mkdir");
+        run_brickline(&mkdir_args);
+
+        let mut file_names: Vec<String> = std::fs::read_dir(&out_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        file_names.sort();
+        std::fs::remove_dir_all(&out_dir).unwrap();
+        assert_eq!(file_names, vec!["batch_1.xml", "batch_2.xml"]);
+    }
+
+    #[test]
+    fn test_join_dry_run() {
+        let left_path = common::get_resource_path("test_wanted_list_1.xml");
+        let right_path = common::get_resource_path("test_wanted_list_2.xml");
+        let out_path = temp_path("joined_dry_run.xml");
+        let output = run_brickline(&[
+            "join",
+            "-l",
+            left_path.to_str().unwrap(),
+            "-r",
+            right_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+            "--dry-run",
+        ]);
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("Dry run: "));
+        assert!(!out_path.exists());
+    }
+
+    #[test]
+    fn test_merge_verbose() {
+        let left_path = common::get_resource_path("test_wanted_list_1.xml");
+        let right_path = common::get_resource_path("test_wanted_list_2.xml");
+        let out_path = temp_path("merged_verbose.xml");
+        let merge_stderr = |flags: &[&str]| {
+            let mut args = vec![
+                "merge",
+                "-i",
+                left_path.to_str().unwrap(),
+                "-i",
+                right_path.to_str().unwrap(),
+                "-o",
+                out_path.to_str().unwrap(),
+            ];
+            args.extend_from_slice(flags);
+            String::from_utf8(run_brickline(&args).stderr).unwrap()
+        };
+
+        assert_eq!(merge_stderr(&[]), "");
+        let stderr = merge_stderr(&["--verbose"]);
+        std::fs::remove_file(&out_path).unwrap();
+        assert!(stderr.contains(&format!("Loaded 3 items from {}", left_path.display())));
+        assert!(stderr.contains("Joined list 2 of 2"));
+        assert!(stderr.contains(&format!("bytes to {}", out_path.display())));
+    }
+}
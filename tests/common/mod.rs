@@ -1,8 +1,11 @@
+#![allow(dead_code)]
+
 use brickline::wanted::{SerdeWantedList, WantedList};
 use brickline::xml_to_string;
 
 use quick_xml::de::from_str;
 
+use std::convert::TryFrom;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
@@ -13,16 +16,22 @@ fn load_resource_directory() -> PathBuf {
     resource_dir
 }
 
-fn get_resource_path(resource_name: &str) -> PathBuf {
+pub fn get_resource_path(resource_name: &str) -> PathBuf {
     let mut resource_path = load_resource_directory();
     resource_path.push(resource_name);
     resource_path
 }
 
+pub fn resource_name_to_serde_wanted_list(resource_name: &str) -> SerdeWantedList {
+    let resource_path = get_resource_path(resource_name);
+    let resource_str = xml_to_string(&resource_path).unwrap();
+    from_str::<SerdeWantedList>(&resource_str).unwrap()
+}
+
 pub fn resource_name_to_wanted_list(resource_name: &str) -> WantedList {
     let resource_path = get_resource_path(resource_name);
     let resource_str = xml_to_string(&resource_path).unwrap();
-    WantedList::from(from_str::<SerdeWantedList>(&resource_str).unwrap())
+    WantedList::try_from(from_str::<SerdeWantedList>(&resource_str).unwrap()).unwrap()
 }
 
 pub fn resource_name_to_string(resource_name: &str) -> String {
@@ -1,9 +1,53 @@
 extern crate brickline;
 
-use brickline::wanted::{ItemID, MinQty, Remarks};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use brickline::colors::BRICKLINK_COLOR_IDS;
+use brickline::error::BricklineError;
+use brickline::wanted::{
+    gen_statistics, gen_statistics_with, Color, Condition, DefaultQty, Indent, Item, ItemBuilder,
+    ItemID, ItemProblem, ItemType, MaxPrice, MinQty, MissingFieldError, Notify,
+    OwnedItemColorHashKey, QtyFilled, Remarks, SerdeWantedList, SortKey, ValidationError,
+    WantedList,
+};
+use brickline::{
+    collapse_duplicates_by, join_inventories_tracked, load_wanted_list_dir, parse_wanted_list,
+    quantity_delta, ExcludeList, ItemFilter, JoinKey, JoinOptions, JoinOrder, JoinStrategy,
+    LoadOptions, Markup, MissingMinQty, OwnedJoinKey, PriceGuide, PricePolicy, QtyChange,
+    RemarksPolicy, SubtractMode,
+};
+
+use quick_xml::de::from_str;
 
 mod common;
 
+fn mixed_wanted_list() -> WantedList {
+    WantedList {
+        items: vec![
+            Item::build_test_item(
+                ItemType::Part,
+                ItemID(String::from("3001")),
+                Some(Color(5)),
+                Some(MinQty(10)),
+            ),
+            Item::build_test_item(
+                ItemType::Part,
+                ItemID(String::from("3622")),
+                Some(Color(11)),
+                Some(MinQty(2)),
+            ),
+            Item::build_test_item(
+                ItemType::Minifig,
+                ItemID(String::from("sw0001")),
+                Some(Color(5)),
+                None,
+            ),
+            Item::build_test_item(ItemType::Set, ItemID(String::from("6020-1")), None, None),
+        ],
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -18,7 +62,7 @@ mod tests {
         let joined_wanted_list_2 = brickline::join_inventories(&wanted_list_2, &wanted_list_1);
 
         // These end up being ordered by ItemID
-        let expected_qty = vec![
+        let expected_qty = [
             (ItemID(String::from("3000")), Some(MinQty(4))),
             (ItemID(String::from("3001")), Some(MinQty(200))),
             (ItemID(String::from("3622")), Some(MinQty(14))),
@@ -48,7 +92,7 @@ mod tests {
         let joined_wanted_list_2 = brickline::join_inventories(&wanted_list_2, &wanted_list_1);
 
         // These end up being ordered by ItemID
-        let expected_qty = vec![
+        let expected_qty = [
             (ItemID(String::from("3001")), Some(MinQty(200))),
             (ItemID(String::from("3039")), None),
             (ItemID(String::from("3622")), Some(MinQty(5))),
@@ -71,4 +115,2267 @@ mod tests {
             Some(Remarks("for MOC AB154A".to_string()))
         );
     }
+
+    #[test]
+    fn test_diff_inventories_1() {
+        let wanted_list_1 = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let wanted_list_2 = common::resource_name_to_wanted_list("test_wanted_list_2.xml");
+
+        let diff = brickline::diff_inventories(&wanted_list_1, &wanted_list_2);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].item_id, ItemID(String::from("3000")));
+        assert_eq!(diff.added[0].min_qty, Some(MinQty(4)));
+
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].item_id, ItemID(String::from("3623")));
+
+        // 3001 has the same MinQty on both sides, so only 3622 changed
+        assert_eq!(
+            diff.changed,
+            vec![QtyChange {
+                item_id: ItemID(String::from("3622")),
+                color: Some(Color(11)),
+                old_qty: Some(MinQty(4)),
+                new_qty: Some(MinQty(10)),
+            }]
+        );
+
+        // Diffing the other way around swaps added/removed and old/new
+        let reverse_diff = brickline::diff_inventories(&wanted_list_2, &wanted_list_1);
+        assert_eq!(reverse_diff.added, diff.removed);
+        assert_eq!(reverse_diff.removed, diff.added);
+        assert_eq!(reverse_diff.changed[0].old_qty, Some(MinQty(10)));
+        assert_eq!(reverse_diff.changed[0].new_qty, Some(MinQty(4)));
+    }
+
+    #[test]
+    fn test_diff_inventories_one_sided_min_qty() {
+        let wanted_list_1 = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let wanted_list_2 = common::resource_name_to_wanted_list("bricklink_example.xml");
+
+        let diff = brickline::diff_inventories(&wanted_list_1, &wanted_list_2);
+
+        // 3622 only has a MinQty in the left list
+        assert_eq!(
+            diff.changed,
+            vec![QtyChange {
+                item_id: ItemID(String::from("3622")),
+                color: Some(Color(11)),
+                old_qty: Some(MinQty(4)),
+                new_qty: None,
+            }]
+        );
+        assert!(diff.to_string().contains("~ 3622 (Black): 4 -> unset"));
+    }
+
+    #[test]
+    fn test_diff_inventories_identical() {
+        let wanted_list_1 = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let wanted_list_2 = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+
+        let diff = brickline::diff_inventories(&wanted_list_1, &wanted_list_2);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_5.xml");
+        // A plain load keeps both 3001/color 5 entries
+        assert_eq!(wanted_list.items.len(), 3);
+
+        let deduped = brickline::collapse_duplicates(&wanted_list);
+        assert_eq!(deduped.items.len(), 2);
+
+        // The first entry keeps its position and metadata, with summed MinQty
+        let merged = &deduped.items[0];
+        assert_eq!(merged.item_id, ItemID(String::from("3001")));
+        assert_eq!(merged.color, Some(Color(5)));
+        assert_eq!(merged.min_qty, Some(MinQty(16)));
+        assert_eq!(merged.condition, Some(Condition::New));
+        assert_eq!(merged.remarks, Some(Remarks(String::from("First"))));
+
+        assert_eq!(deduped.items[1], wanted_list.items[1]);
+    }
+
+    fn item_ids(wanted_list: &WantedList) -> Vec<&str> {
+        wanted_list
+            .items
+            .iter()
+            .map(|item| item.item_id.0.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_by_color() {
+        let item_filter = ItemFilter {
+            color: Some(Color(5)),
+            ..ItemFilter::default()
+        };
+        let filtered = mixed_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(item_ids(&filtered), vec!["3001", "sw0001"]);
+    }
+
+    #[test]
+    fn test_filter_by_item_type() {
+        let item_filter = ItemFilter {
+            item_type: Some(ItemType::Part),
+            ..ItemFilter::default()
+        };
+        let filtered = mixed_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(item_ids(&filtered), vec!["3001", "3622"]);
+    }
+
+    #[test]
+    fn test_filter_combined() {
+        let item_filter = ItemFilter {
+            color: Some(Color(5)),
+            item_type: Some(ItemType::Part),
+            min_qty_gte: Some(5),
+            ..ItemFilter::default()
+        };
+        let filtered = mixed_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(item_ids(&filtered), vec!["3001"]);
+
+        // Items without a MinQty count as wanting 1
+        let item_filter = ItemFilter {
+            min_qty_gte: Some(1),
+            ..ItemFilter::default()
+        };
+        let filtered = mixed_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(filtered, mixed_wanted_list());
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut wanted_list = common::resource_name_to_wanted_list("bricklink_example.xml");
+        wanted_list.sort();
+        assert_eq!(item_ids(&wanted_list), vec!["3001", "3039", "3622"]);
+
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.items.push(Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            None,
+            None,
+        ));
+        wanted_list.sort();
+        assert_eq!(
+            item_ids(&wanted_list),
+            vec!["6020-1", "3001", "3001", "3622", "sw0001"]
+        );
+        // The colorless 3001 sorts ahead of the colored one
+        assert_eq!(wanted_list.items[1].color, None);
+        assert_eq!(wanted_list.items[2].color, Some(Color(5)));
+
+        // The sorted list still serializes to a valid wanted list
+        let stringified = String::try_from(wanted_list).unwrap();
+        let reparsed =
+            WantedList::try_from(from_str::<SerdeWantedList>(&stringified).unwrap()).unwrap();
+        assert_eq!(
+            item_ids(&reparsed),
+            vec!["6020-1", "3001", "3001", "3622", "sw0001"]
+        );
+    }
+
+    fn join_with_strategy(
+        left_qty: Option<MinQty>,
+        right_qty: Option<MinQty>,
+        strategy: JoinStrategy,
+    ) -> Item {
+        let mut left_item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            left_qty,
+        );
+        left_item.remarks = Some(Remarks(String::from("Left")));
+        let mut right_item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            right_qty,
+        );
+        right_item.remarks = Some(Remarks(String::from("Right")));
+        let left_inventory = WantedList {
+            items: vec![left_item],
+        };
+        let right_inventory = WantedList {
+            items: vec![right_item],
+        };
+
+        let join_options = JoinOptions {
+            strategy,
+            ..JoinOptions::default()
+        };
+        let joined =
+            brickline::join_inventories_with(&left_inventory, &right_inventory, &join_options);
+        assert_eq!(joined.items.len(), 1);
+        let joined_item = joined.items[0].clone();
+        // Every strategy keeps the left metadata
+        assert_eq!(joined_item.remarks, Some(Remarks(String::from("Left"))));
+        joined_item
+    }
+
+    #[test]
+    fn test_join_strategy_sum() {
+        let item = join_with_strategy(Some(MinQty(4)), Some(MinQty(10)), JoinStrategy::Sum);
+        assert_eq!(item.min_qty, Some(MinQty(14)));
+    }
+
+    #[test]
+    fn test_join_strategy_max() {
+        let item = join_with_strategy(Some(MinQty(4)), Some(MinQty(10)), JoinStrategy::Max);
+        assert_eq!(item.min_qty, Some(MinQty(10)));
+        let item = join_with_strategy(Some(MinQty(10)), Some(MinQty(4)), JoinStrategy::Max);
+        assert_eq!(item.min_qty, Some(MinQty(10)));
+        // A missing MinQty counts as 1
+        let item = join_with_strategy(None, Some(MinQty(4)), JoinStrategy::Max);
+        assert_eq!(item.min_qty, Some(MinQty(4)));
+    }
+
+    #[test]
+    fn test_join_strategy_keep_left() {
+        let item = join_with_strategy(Some(MinQty(4)), Some(MinQty(10)), JoinStrategy::KeepLeft);
+        assert_eq!(item.min_qty, Some(MinQty(4)));
+    }
+
+    #[test]
+    fn test_join_strategy_keep_right() {
+        let item = join_with_strategy(Some(MinQty(4)), Some(MinQty(10)), JoinStrategy::KeepRight);
+        assert_eq!(item.min_qty, Some(MinQty(10)));
+        let item = join_with_strategy(Some(MinQty(4)), None, JoinStrategy::KeepRight);
+        assert_eq!(item.min_qty, None);
+    }
+
+    #[test]
+    fn test_validate_colors() {
+        let mut wanted_list = common::resource_name_to_wanted_list("test_wanted_list_4.xml");
+        assert!(wanted_list.validate_colors().is_empty());
+
+        // Color::is_valid binary searches the catalog
+        assert!(BRICKLINK_COLOR_IDS.windows(2).all(|ids| ids[0] < ids[1]));
+
+        wanted_list.items.push(Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(9999)),
+            None,
+        ));
+        assert_eq!(
+            wanted_list.validate_colors(),
+            vec![(ItemID(String::from("3001")), Color(9999))]
+        );
+    }
+
+    #[test]
+    fn test_validate_valid_lists() {
+        for resource_name in [
+            "bricklink_example.xml",
+            "test_wanted_list_1.xml",
+            "test_wanted_list_4.xml",
+        ]
+        .iter()
+        {
+            let serde_wanted_list = common::resource_name_to_serde_wanted_list(resource_name);
+            assert!(brickline::wanted::validate(&serde_wanted_list).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_validate_collects_every_problem() {
+        let serde_wanted_list =
+            common::resource_name_to_serde_wanted_list("test_wanted_list_invalid.xml");
+        let validation_errors = brickline::wanted::validate(&serde_wanted_list);
+
+        // The last item has QtyFilled == MinQty, which is fine
+        assert_eq!(
+            validation_errors,
+            vec![
+                ValidationError::UnknownItemType {
+                    index: 0,
+                    item_id: String::from("3001"),
+                    item_type: String::from("Z"),
+                },
+                ValidationError::UnknownColor {
+                    index: 1,
+                    item_id: String::from("3002"),
+                    color: 9999,
+                },
+                ValidationError::NonPositiveMinQty {
+                    index: 1,
+                    item_id: String::from("3002"),
+                    min_qty: 0,
+                },
+                ValidationError::NonPositiveMinQty {
+                    index: 2,
+                    item_id: String::from("3003"),
+                    min_qty: -5,
+                },
+                ValidationError::QtyFilledExceedsMinQty {
+                    index: 3,
+                    item_id: String::from("3004"),
+                    qty_filled: 6,
+                    min_qty: 4,
+                },
+                ValidationError::UnparseableMaxPrice {
+                    index: 4,
+                    item_id: String::from("3005"),
+                    max_price: String::from("abc"),
+                },
+            ]
+        );
+        assert_eq!(
+            validation_errors[0].to_string(),
+            "Item 0 (3001): unknown item type \"Z\""
+        );
+    }
+
+    #[test]
+    fn test_intersect_inventories() {
+        let wanted_list_1 = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let wanted_list_2 = common::resource_name_to_wanted_list("test_wanted_list_2.xml");
+
+        let intersected = brickline::intersect_inventories(&wanted_list_1, &wanted_list_2);
+
+        // 3623 is only on the left and 3000 is only on the right
+        assert_eq!(item_ids(&intersected), vec!["3001", "3622"]);
+        assert_eq!(intersected.items[0].min_qty, Some(MinQty(100)));
+        assert_eq!(intersected.items[1].min_qty, Some(MinQty(4)));
+        // Metadata comes from the left list
+        assert_eq!(
+            intersected.items[0].remarks,
+            Some(Remarks(String::from("Testing")))
+        );
+        assert_eq!(intersected.items[0].condition, None);
+
+        let intersected = brickline::intersect_inventories(&wanted_list_2, &wanted_list_1);
+        assert_eq!(item_ids(&intersected), vec!["3001", "3622"]);
+        assert_eq!(intersected.items[1].min_qty, Some(MinQty(4)));
+        assert_eq!(intersected.items[0].remarks, None);
+        assert_eq!(intersected.items[0].condition, Some(Condition::New));
+    }
+
+    #[test]
+    fn test_intersect_duplicate_entries() {
+        let mut first_3001 = filled_item("3001", Some(4), None);
+        first_3001.remarks = Some(Remarks::from("First"));
+        let mut second_3001 = filled_item("3001", Some(5), None);
+        second_3001.remarks = Some(Remarks::from("Second"));
+        let left = WantedList {
+            items: vec![first_3001, second_3001],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(6), None),
+                filled_item("3001", Some(1), None),
+            ],
+        };
+
+        // 4 + 5 on the left against 6 + 1 on the right
+        let intersected = brickline::intersect_inventories(&left, &right);
+        assert_eq!(min_qtys(&intersected), vec![Some(MinQty(7))]);
+        assert_eq!(intersected.items[0].remarks, Some(Remarks::from("First")));
+
+        let intersected = brickline::intersect_inventories(&right, &left);
+        assert_eq!(min_qtys(&intersected), vec![Some(MinQty(7))]);
+    }
+
+    #[test]
+    fn test_item_builder() {
+        let item = ItemBuilder::new()
+            .item_type(ItemType::Part)
+            .item_id(ItemID(String::from("3001")))
+            .color(Color(5))
+            .min_qty(MinQty(10))
+            .condition(Condition::Used)
+            .remarks(Remarks(String::from("for MOC AB154A")))
+            .build()
+            .unwrap();
+
+        let mut expected = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            Some(MinQty(10)),
+        );
+        expected.condition = Some(Condition::Used);
+        expected.remarks = Some(Remarks(String::from("for MOC AB154A")));
+        assert_eq!(item, expected);
+    }
+
+    #[test]
+    fn test_item_builder_requires_type_and_id() {
+        let missing_type = ItemBuilder::new()
+            .item_id(ItemID(String::from("3001")))
+            .build();
+        assert_eq!(missing_type, Err(MissingFieldError { field: "ITEMTYPE" }));
+
+        let missing_id = ItemBuilder::new().item_type(ItemType::Part).build();
+        assert_eq!(missing_id, Err(MissingFieldError { field: "ITEMID" }));
+    }
+
+    #[test]
+    fn test_statistics_total_max_price() {
+        let priced = ItemBuilder::new()
+            .item_type(ItemType::Part)
+            .item_id(ItemID(String::from("3001")))
+            .max_price(MaxPrice(25))
+            .min_qty(MinQty(4))
+            .build()
+            .unwrap();
+        // No MinQty counts as 1
+        let priced_no_qty = ItemBuilder::new()
+            .item_type(ItemType::Part)
+            .item_id(ItemID(String::from("3002")))
+            .max_price(MaxPrice(110))
+            .build()
+            .unwrap();
+        // Bricklink uses -1.00 for "no maximum price"
+        let no_maximum = ItemBuilder::new()
+            .item_type(ItemType::Part)
+            .item_id(ItemID(String::from("3003")))
+            .max_price(MaxPrice(-100))
+            .min_qty(MinQty(10))
+            .build()
+            .unwrap();
+        let unpriced =
+            Item::build_test_item(ItemType::Part, ItemID(String::from("3004")), None, None);
+        let wanted_list = WantedList {
+            items: vec![priced, priced_no_qty, no_maximum, unpriced],
+        };
+
+        let statistics = gen_statistics(&wanted_list);
+        assert_eq!(statistics.total_max_price, MaxPrice(210));
+        assert_eq!(statistics.priced_item_count, 2);
+        assert_eq!(statistics.unpriced_item_count, 2);
+        assert!(statistics.to_string().contains("Total Max Price: 2.10"));
+    }
+
+    #[test]
+    fn test_statistics_counts_by_type() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.items.push(Item::build_test_item(
+            ItemType::Minifig,
+            ItemID(String::from("sw0002")),
+            None,
+            None,
+        ));
+
+        let statistics = gen_statistics(&wanted_list);
+        let mut expected = BTreeMap::new();
+        expected.insert(ItemType::Set, 1);
+        expected.insert(ItemType::Part, 2);
+        expected.insert(ItemType::Minifig, 2);
+        assert_eq!(statistics.counts_by_type, expected);
+        assert!(statistics
+            .to_string()
+            .contains("Item Counts By Type: Set (1), Part (2), Minifig (2)"));
+    }
+
+    fn join_with_key(left_item: Item, right_item: Item, key: JoinKey) -> WantedList {
+        let left_inventory = WantedList {
+            items: vec![left_item],
+        };
+        let right_inventory = WantedList {
+            items: vec![right_item],
+        };
+        let join_options = JoinOptions {
+            key,
+            ..JoinOptions::default()
+        };
+        brickline::join_inventories_with(&left_inventory, &right_inventory, &join_options)
+    }
+
+    #[test]
+    fn test_join_key_condition() {
+        let mut new_item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            Some(MinQty(4)),
+        );
+        new_item.condition = Some(Condition::New);
+        let mut used_item = new_item.clone();
+        used_item.condition = Some(Condition::Used);
+        used_item.min_qty = Some(MinQty(10));
+
+        let joined = join_with_key(new_item.clone(), used_item.clone(), JoinKey::IdColor);
+        assert_eq!(joined.items.len(), 1);
+        assert_eq!(joined.items[0].min_qty, Some(MinQty(14)));
+        assert_eq!(joined.items[0].condition, Some(Condition::New));
+
+        let joined = join_with_key(new_item, used_item, JoinKey::IdColorCondition);
+        assert_eq!(joined.items.len(), 2);
+        assert_eq!(joined.items[0].condition, Some(Condition::New));
+        assert_eq!(joined.items[0].min_qty, Some(MinQty(4)));
+        assert_eq!(joined.items[1].condition, Some(Condition::Used));
+        assert_eq!(joined.items[1].min_qty, Some(MinQty(10)));
+    }
+
+    #[test]
+    fn test_join_key_id_only() {
+        let red_item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            Some(MinQty(4)),
+        );
+        let black_item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(11)),
+            Some(MinQty(10)),
+        );
+
+        let joined = join_with_key(red_item.clone(), black_item.clone(), JoinKey::IdColor);
+        assert_eq!(joined.items.len(), 2);
+
+        // Merged across colors, keeping the left color
+        let joined = join_with_key(red_item, black_item, JoinKey::IdOnly);
+        assert_eq!(joined.items.len(), 1);
+        assert_eq!(joined.items[0].color, Some(Color(5)));
+        assert_eq!(joined.items[0].min_qty, Some(MinQty(14)));
+    }
+
+    #[test]
+    fn test_chunk_exact_multiple() {
+        let wanted_list = mixed_wanted_list();
+        let chunks = wanted_list.chunk(2);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(item_ids(&chunks[0]), vec!["3001", "3622"]);
+        assert_eq!(item_ids(&chunks[1]), vec!["sw0001", "6020-1"]);
+        assert_eq!(chunks[1].items[1], wanted_list.items[3]);
+    }
+
+    #[test]
+    fn test_chunk_remainder() {
+        let wanted_list = mixed_wanted_list();
+        let chunks = wanted_list.chunk(3);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(item_ids(&chunks[0]), vec!["3001", "3622", "sw0001"]);
+        assert_eq!(item_ids(&chunks[1]), vec!["6020-1"]);
+
+        let chunks = wanted_list.chunk(10);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0], wanted_list);
+    }
+
+    fn filled_item(item_id: &str, min_qty: Option<i32>, qty_filled: Option<i32>) -> Item {
+        let mut item = Item::build_test_item(
+            ItemType::Part,
+            ItemID::from(item_id),
+            Some(Color(5)),
+            min_qty.map(MinQty),
+        );
+        item.qty_filled = qty_filled.map(QtyFilled);
+        item.remarks = Some(Remarks::from("Keep me"));
+        item
+    }
+
+    #[test]
+    fn test_remaining_qty() {
+        assert_eq!(filled_item("3001", Some(100), Some(40)).remaining_qty(), 60);
+        assert_eq!(filled_item("3001", Some(100), Some(100)).remaining_qty(), 0);
+        assert_eq!(filled_item("3001", Some(10), Some(12)).remaining_qty(), 0);
+        assert_eq!(filled_item("3001", Some(10), None).remaining_qty(), 10);
+        assert_eq!(filled_item("3001", None, None).remaining_qty(), 1);
+        assert_eq!(filled_item("3001", None, Some(1)).remaining_qty(), 0);
+        // A negative QtyFilled counts as 0 rather than adding to MinQty
+        assert_eq!(filled_item("3001", Some(3), Some(-5)).remaining_qty(), 3);
+        assert_eq!(
+            filled_item("3001", Some(i32::MIN), Some(i32::MAX)).remaining_qty(),
+            0
+        );
+        assert_eq!(
+            filled_item("3001", Some(3), Some(i32::MIN)).remaining_qty(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_remaining() {
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(100), Some(40)),
+                filled_item("3002", Some(10), Some(10)),
+                filled_item("3003", Some(7), None),
+            ],
+        };
+        let remaining = wanted_list.remaining();
+        assert_eq!(item_ids(&remaining), vec!["3001", "3003"]);
+
+        assert_eq!(remaining.items[0].min_qty, Some(MinQty(60)));
+        assert_eq!(remaining.items[0].qty_filled, None);
+        assert_eq!(
+            remaining.items[0].remarks,
+            Some(Remarks(String::from("Keep me")))
+        );
+        assert_eq!(remaining.items[1], wanted_list.items[2]);
+    }
+
+    #[test]
+    fn test_join_preserves_left_order() {
+        let left_inventory = WantedList {
+            items: vec![
+                filled_item("3622", Some(1), None),
+                filled_item("3001", Some(2), None),
+                filled_item("3010", Some(3), None),
+            ],
+        };
+        let right_inventory = WantedList {
+            items: vec![
+                filled_item("3003", Some(4), None),
+                filled_item("3001", Some(5), None),
+                filled_item("3002", Some(6), None),
+            ],
+        };
+
+        let sorted = brickline::join_inventories(&left_inventory, &right_inventory);
+        assert_eq!(
+            item_ids(&sorted),
+            vec!["3001", "3002", "3003", "3010", "3622"]
+        );
+
+        let join_options = JoinOptions {
+            order: JoinOrder::PreserveLeft,
+            ..JoinOptions::default()
+        };
+        let preserved =
+            brickline::join_inventories_with(&left_inventory, &right_inventory, &join_options);
+        assert_eq!(
+            item_ids(&preserved),
+            vec!["3622", "3001", "3010", "3003", "3002"]
+        );
+        assert_eq!(preserved.items[1].min_qty, Some(MinQty(7)));
+    }
+
+    fn load_resource(resource_name: &str) -> Result<WantedList, BricklineError> {
+        let resource_path = common::get_resource_path(resource_name);
+        brickline::file_to_wanted_list(resource_path.to_str().unwrap())
+            .map(|(wanted_list, _)| wanted_list)
+    }
+
+    #[test]
+    fn test_malformed_xml_is_xml_error() {
+        match load_resource("test_wanted_list_malformed.xml") {
+            Err(BricklineError::Xml(..)) => (),
+            other => panic!("Expected an Xml error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_file_is_io_error() {
+        match load_resource("does_not_exist.xml") {
+            Err(BricklineError::Io(_)) => (),
+            other => panic!("Expected an Io error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.scale(2);
+        let min_qtys: Vec<Option<MinQty>> = wanted_list
+            .items
+            .iter()
+            .map(|item| item.min_qty.clone())
+            .collect();
+        assert_eq!(
+            min_qtys,
+            vec![
+                Some(MinQty(20)),
+                Some(MinQty(4)),
+                Some(MinQty(2)),
+                Some(MinQty(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_statistics_default_qty() {
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), Some(4)),
+                filled_item("3622", None, None),
+            ],
+        };
+        let default_one = gen_statistics_with(&wanted_list, DefaultQty::One);
+        assert_eq!(default_one.total_parts, 11);
+        assert_eq!(default_one.total_parts_remaining, 7);
+        assert_eq!(default_one, gen_statistics(&wanted_list));
+
+        let excluded = gen_statistics_with(&wanted_list, DefaultQty::Exclude);
+        assert_eq!(excluded.total_parts, 10);
+        assert_eq!(excluded.total_parts_remaining, 6);
+        assert_eq!(excluded.total_items, 2);
+    }
+
+    #[test]
+    fn test_join_default_qty_exclude() {
+        let left = WantedList {
+            items: vec![
+                filled_item("3001", None, None),
+                filled_item("3622", None, None),
+            ],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), None),
+                filled_item("3622", None, None),
+            ],
+        };
+        let joined = brickline::join_inventories(&left, &right);
+        assert_eq!(min_qtys(&joined), vec![Some(MinQty(11)), Some(MinQty(2))]);
+
+        let join_options = JoinOptions {
+            default_qty: DefaultQty::Exclude,
+            ..JoinOptions::default()
+        };
+        let joined = brickline::join_inventories_with(&left, &right, &join_options);
+        assert_eq!(min_qtys(&joined), vec![Some(MinQty(10)), None]);
+    }
+
+    #[test]
+    fn test_scale_saturates() {
+        let mut wanted_list = WantedList {
+            items: vec![filled_item("3001", Some(i32::MAX - 10), None)],
+        };
+        wanted_list.scale(3);
+        assert_eq!(min_qtys(&wanted_list), vec![Some(MinQty(i32::MAX))]);
+    }
+
+    #[test]
+    fn test_scale_identity() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.scale(1);
+        let expected = mixed_wanted_list();
+        assert_eq!(wanted_list.items[0], expected.items[0]);
+        assert_eq!(wanted_list.items[1], expected.items[1]);
+        // A missing MinQty is written out as 1
+        assert_eq!(wanted_list.items[3].min_qty, Some(MinQty(1)));
+    }
+
+    #[test]
+    fn test_items_with_multiple_colors() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.items.push(Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(11)),
+            Some(MinQty(3)),
+        ));
+        let multiple_colors = wanted_list.items_with_multiple_colors();
+        let mut expected = BTreeMap::new();
+        expected.insert(ItemID(String::from("3001")), vec![Color(5), Color(11)]);
+        assert_eq!(multiple_colors, expected);
+
+        assert!(mixed_wanted_list().items_with_multiple_colors().is_empty());
+    }
+
+    fn join_remarks(left: Option<&str>, right: Option<&str>, remarks: RemarksPolicy) -> Item {
+        let mut left_item = filled_item("3001", Some(1), None);
+        left_item.remarks = left.map(|r| Remarks(String::from(r)));
+        let mut right_item = filled_item("3001", Some(2), None);
+        right_item.remarks = right.map(|r| Remarks(String::from(r)));
+        let join_options = JoinOptions {
+            remarks,
+            ..JoinOptions::default()
+        };
+        let joined = brickline::join_inventories_with(
+            &WantedList {
+                items: vec![left_item],
+            },
+            &WantedList {
+                items: vec![right_item],
+            },
+            &join_options,
+        );
+        joined.items[0].clone()
+    }
+
+    #[test]
+    fn test_join_concatenates_remarks() {
+        let joined = join_remarks(Some("left"), Some("right"), RemarksPolicy::Concatenate);
+        assert_eq!(joined.remarks, Some(Remarks(String::from("left; right"))));
+        assert_eq!(joined.min_qty, Some(MinQty(3)));
+
+        let joined = join_remarks(Some("same"), Some("same"), RemarksPolicy::Concatenate);
+        assert_eq!(joined.remarks, Some(Remarks(String::from("same"))));
+
+        let joined = join_remarks(None, Some("right"), RemarksPolicy::Concatenate);
+        assert_eq!(joined.remarks, Some(Remarks(String::from("right"))));
+    }
+
+    #[test]
+    fn test_join_remarks_keep_policies() {
+        let joined = join_remarks(Some("left"), Some("right"), RemarksPolicy::KeepLeft);
+        assert_eq!(joined.remarks, Some(Remarks(String::from("left"))));
+
+        let joined = join_remarks(Some("left"), Some("right"), RemarksPolicy::KeepRight);
+        assert_eq!(joined.remarks, Some(Remarks(String::from("right"))));
+    }
+
+    #[test]
+    fn test_statistics_json() {
+        let statistics = gen_statistics(&mixed_wanted_list());
+        let json = serde_json::to_string(&statistics).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["total_items"], 4);
+        assert_eq!(value["total_parts"], 14);
+        assert_eq!(value["total_max_price"], "0.00");
+        assert_eq!(value["counts_by_type"]["Part"], 2);
+        // The sets behind the unique counts aren't serialized
+        assert!(value.get("item_color_set").is_none());
+        assert!(value.get("color_set").is_none());
+    }
+
+    #[test]
+    fn test_item_id_normalized() {
+        let normalized = |id: &str| ItemID(String::from(id)).normalized().0;
+        assert_eq!(normalized("3001old"), "3001");
+        assert_eq!(normalized("3001OLD"), "3001");
+        assert_eq!(normalized("3001"), "3001");
+        assert_eq!(normalized("bb"), "bb");
+        assert_eq!(normalized("a"), "a");
+        // Letter suffixes mark different molds and figures, so they're kept
+        // unless asked for
+        assert_eq!(normalized("3062a"), "3062a");
+        assert_eq!(normalized("3062b"), "3062b");
+        assert_eq!(normalized("sw0001a"), "sw0001a");
+        let with_letters = |id: &str| ItemID(String::from(id)).normalized_with(&["a", "b"]).0;
+        assert_eq!(with_letters("3062a"), "3062");
+        assert_eq!(with_letters("3062b"), "3062");
+
+        let id = ItemID(String::from("3001x"));
+        assert_eq!(id.normalized(), id);
+        assert_eq!(id.normalized_with(&["x"]), ItemID(String::from("3001")));
+    }
+
+    #[test]
+    fn test_join_normalize_ids() {
+        let left_inventory = WantedList {
+            items: vec![filled_item("3001", Some(4), None)],
+        };
+        let right_inventory = WantedList {
+            items: vec![filled_item("3001a", Some(6), None)],
+        };
+
+        let joined = brickline::join_inventories(&left_inventory, &right_inventory);
+        assert_eq!(item_ids(&joined), vec!["3001", "3001a"]);
+
+        let join_options = JoinOptions {
+            id_suffixes: Some(vec![String::from("a")]),
+            ..JoinOptions::default()
+        };
+        let joined =
+            brickline::join_inventories_with(&left_inventory, &right_inventory, &join_options);
+        assert_eq!(item_ids(&joined), vec!["3001"]);
+        assert_eq!(joined.items[0].min_qty, Some(MinQty(10)));
+
+        // The retained Item keeps its original ItemID
+        let joined =
+            brickline::join_inventories_with(&right_inventory, &left_inventory, &join_options);
+        assert_eq!(item_ids(&joined), vec!["3001a"]);
+    }
+
+    #[test]
+    fn test_join_many() {
+        let first = WantedList {
+            items: vec![
+                filled_item("3001", Some(1), None),
+                filled_item("3002", Some(2), None),
+            ],
+        };
+        let second = WantedList {
+            items: vec![filled_item("3001", Some(10), None)],
+        };
+        let third = WantedList {
+            items: vec![
+                filled_item("3001", Some(100), None),
+                filled_item("3003", None, None),
+            ],
+        };
+        let joined = brickline::join_many(&[first, second, third]);
+        assert_eq!(item_ids(&joined), vec!["3001", "3002", "3003"]);
+        let min_qtys: Vec<Option<MinQty>> = joined
+            .items
+            .iter()
+            .map(|item| item.min_qty.clone())
+            .collect();
+        assert_eq!(min_qtys, vec![Some(MinQty(111)), Some(MinQty(2)), None]);
+
+        assert!(brickline::join_many(&[]).items.is_empty());
+    }
+
+    #[test]
+    fn test_statistics_counts_by_condition() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.items[0].condition = Some(Condition::New);
+        wanted_list.items[1].condition = Some(Condition::Used);
+        wanted_list.items[2].condition = Some(Condition::New);
+
+        let statistics = gen_statistics(&wanted_list);
+        let mut expected = BTreeMap::new();
+        expected.insert(Some(Condition::New), 2);
+        expected.insert(Some(Condition::Used), 1);
+        expected.insert(None, 1);
+        assert_eq!(statistics.counts_by_condition, expected);
+        assert!(statistics
+            .to_string()
+            .contains("Item Counts By Condition: New (2), Used (1), Unspecified (1)"));
+
+        let value = serde_json::to_value(&statistics).unwrap();
+        assert_eq!(value["counts_by_condition"]["Unspecified"], 1);
+        assert_eq!(value["counts_by_condition"]["New"], 2);
+    }
+
+    #[test]
+    fn test_join_inventories_owned_matches_join() {
+        let left_inventory = common::resource_name_to_wanted_list("test_wanted_list_3.xml");
+        let right_inventory = common::resource_name_to_wanted_list("test_wanted_list_4.xml");
+        let joined = brickline::join_inventories(&left_inventory, &right_inventory);
+        let joined_owned = brickline::join_inventories_owned(left_inventory, right_inventory);
+        assert_eq!(joined_owned, joined);
+
+        // Duplicates within a list and missing MinQty
+        let left_inventory = common::resource_name_to_wanted_list("test_wanted_list_5.xml");
+        let right_inventory = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let joined = brickline::join_inventories(&left_inventory, &right_inventory);
+        let joined_owned = brickline::join_inventories_owned(left_inventory, right_inventory);
+        assert_eq!(joined_owned, joined);
+    }
+
+    fn join_missing_left_qty(missing_min_qty: MissingMinQty, strategy: JoinStrategy) -> Item {
+        let left_inventory = WantedList {
+            items: vec![filled_item("3001", None, None)],
+        };
+        let right_inventory = WantedList {
+            items: vec![filled_item("3001", Some(10), None)],
+        };
+        let join_options = JoinOptions {
+            strategy,
+            missing_min_qty,
+            ..JoinOptions::default()
+        };
+        let joined =
+            brickline::join_inventories_with(&left_inventory, &right_inventory, &join_options);
+        joined.items[0].clone()
+    }
+
+    #[test]
+    fn test_join_missing_left_qty_implicit_one() {
+        let joined = join_missing_left_qty(MissingMinQty::ImplicitOne, JoinStrategy::Sum);
+        assert_eq!(joined.min_qty, Some(MinQty(11)));
+        let joined = join_missing_left_qty(MissingMinQty::ImplicitOne, JoinStrategy::KeepLeft);
+        assert_eq!(joined.min_qty, None);
+    }
+
+    #[test]
+    fn test_join_missing_left_qty_adopt_right() {
+        let joined = join_missing_left_qty(MissingMinQty::AdoptRight, JoinStrategy::Sum);
+        assert_eq!(joined.min_qty, Some(MinQty(10)));
+        let joined = join_missing_left_qty(MissingMinQty::AdoptRight, JoinStrategy::KeepLeft);
+        assert_eq!(joined.min_qty, Some(MinQty(10)));
+        assert_eq!(joined.remarks, Some(Remarks(String::from("Keep me"))));
+    }
+
+    #[test]
+    fn test_top_by_qty() {
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3003", Some(5), None),
+                filled_item("3001", None, None),
+                filled_item("3004", Some(20), None),
+                filled_item("3002", Some(5), None),
+                filled_item("3005", Some(1), None),
+            ],
+        };
+        let top_ids = |n: usize| -> Vec<&str> {
+            wanted_list
+                .top_by_qty(n)
+                .iter()
+                .map(|item| item.item_id.0.as_str())
+                .collect()
+        };
+        assert_eq!(top_ids(3), vec!["3004", "3002", "3003"]);
+        // A missing MinQty ties with 1
+        assert_eq!(top_ids(10), vec!["3004", "3002", "3003", "3001", "3005"]);
+        assert!(top_ids(0).is_empty());
+    }
+
+    #[test]
+    fn test_join_keeps_left_alternate() {
+        let mut left_item = filled_item("3001", Some(1), None);
+        left_item.alternate = Some(Notify::Y);
+        let mut right_item = filled_item("3001", Some(2), None);
+        right_item.alternate = Some(Notify::N);
+        right_item.counterpart = Some(Notify::Y);
+        let joined = brickline::join_inventories(
+            &WantedList {
+                items: vec![left_item],
+            },
+            &WantedList {
+                items: vec![right_item],
+            },
+        );
+        assert_eq!(joined.items[0].alternate, Some(Notify::Y));
+        assert_eq!(joined.items[0].counterpart, None);
+    }
+
+    #[test]
+    fn test_merge_colors() {
+        let mut wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(2), None),
+                filled_item("3622", Some(4), None),
+                Item::build_test_item(
+                    ItemType::Part,
+                    ItemID(String::from("3001")),
+                    Some(Color(11)),
+                    Some(MinQty(3)),
+                ),
+            ],
+        };
+        let untouched = wanted_list.items[1].clone();
+        wanted_list.merge_colors();
+
+        assert_eq!(wanted_list.items.len(), 2);
+        assert_eq!(wanted_list.items[0].item_id, ItemID(String::from("3001")));
+        assert_eq!(wanted_list.items[0].color, None);
+        assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(5)));
+        assert_eq!(wanted_list.items[1], untouched);
+    }
+
+    #[test]
+    fn test_malformed_xml_has_position() {
+        match load_resource("test_wanted_list_malformed.xml") {
+            Err(BricklineError::Xml(_, Some(position))) => {
+                assert_eq!(position.line, 7);
+                assert!(position.column > 0);
+            }
+            other => panic!("Expected a positioned Xml error, got {:?}", other),
+        }
+    }
+
+    fn duplicated_wanted_list() -> WantedList {
+        let mut new_red = filled_item("3001", Some(2), None);
+        new_red.condition = Some(Condition::New);
+        let mut used_red = filled_item("3001", Some(3), None);
+        used_red.condition = Some(Condition::Used);
+        let mut new_black = filled_item("3001", Some(4), None);
+        new_black.condition = Some(Condition::New);
+        new_black.color = Some(Color(11));
+        WantedList {
+            items: vec![new_red, used_red, new_black],
+        }
+    }
+
+    fn min_qtys(wanted_list: &WantedList) -> Vec<Option<MinQty>> {
+        wanted_list
+            .items
+            .iter()
+            .map(|item| item.min_qty.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_collapse_duplicates_by_id_color() {
+        let deduped = collapse_duplicates_by(&duplicated_wanted_list(), JoinKey::IdColor);
+        assert_eq!(min_qtys(&deduped), vec![Some(MinQty(5)), Some(MinQty(4))]);
+        assert_eq!(deduped.items[0].condition, Some(Condition::New));
+    }
+
+    #[test]
+    fn test_collapse_duplicates_by_id_color_condition() {
+        let wanted_list = duplicated_wanted_list();
+        let deduped = collapse_duplicates_by(&wanted_list, JoinKey::IdColorCondition);
+        assert_eq!(deduped, wanted_list);
+    }
+
+    #[test]
+    fn test_collapse_duplicates_by_id() {
+        let deduped = collapse_duplicates_by(&duplicated_wanted_list(), JoinKey::IdOnly);
+        assert_eq!(min_qtys(&deduped), vec![Some(MinQty(9))]);
+        assert_eq!(deduped.items[0].color, Some(Color(5)));
+
+        let stringified = String::try_from(deduped.clone()).unwrap();
+        let reparsed =
+            WantedList::try_from(from_str::<SerdeWantedList>(&stringified).unwrap()).unwrap();
+        assert_eq!(reparsed, deduped);
+    }
+
+    #[test]
+    fn test_statistics_total_parts_remaining() {
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(100), Some(30)),
+                filled_item("3622", Some(5), Some(10)),
+                filled_item("3623", None, None),
+            ],
+        };
+        let statistics = gen_statistics(&wanted_list);
+        assert_eq!(statistics.total_parts, 106);
+        assert_eq!(statistics.total_parts_remaining, 71);
+        assert!(statistics
+            .to_string()
+            .contains("Total Parts Remaining: 71, "));
+    }
+
+    #[test]
+    fn test_statistics_negative_qty_filled() {
+        let wanted_list = WantedList {
+            items: vec![filled_item("3001", Some(5), Some(i32::MIN))],
+        };
+        let statistics = gen_statistics(&wanted_list);
+        assert_eq!(statistics.total_parts, 5);
+        // A negative QtyFilled counts as nothing filled, as in remaining_qty
+        assert_eq!(statistics.total_parts_remaining, 5);
+    }
+
+    /// A fresh directory holding one valid and one malformed wanted list
+    fn mixed_directory(name: &str) -> std::path::PathBuf {
+        let mut dir_path = std::env::temp_dir();
+        dir_path.push(format!("brickline_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir_path).unwrap();
+        for (resource_name, file_name) in [
+            ("test_wanted_list_1.xml", "a_valid.xml"),
+            ("test_wanted_list_malformed.xml", "b_malformed.xml"),
+        ] {
+            std::fs::copy(
+                common::get_resource_path(resource_name),
+                dir_path.join(file_name),
+            )
+            .unwrap();
+        }
+        dir_path
+    }
+
+    #[test]
+    fn test_load_wanted_list_dir_continue_on_error() {
+        let dir_path = mixed_directory("continue_on_error");
+        let load_options = LoadOptions {
+            continue_on_error: true,
+            ..LoadOptions::default()
+        };
+        let directory_load = load_wanted_list_dir(&dir_path, &load_options).unwrap();
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        assert_eq!(directory_load.loaded.len(), 1);
+        assert_eq!(directory_load.loaded[0].0, dir_path.join("a_valid.xml"));
+        assert_eq!(
+            directory_load.loaded[0].1,
+            common::resource_name_to_wanted_list("test_wanted_list_1.xml")
+        );
+        assert_eq!(directory_load.failed.len(), 1);
+        assert_eq!(directory_load.failed[0].0, dir_path.join("b_malformed.xml"));
+        match directory_load.failed[0].1 {
+            BricklineError::Xml(..) => (),
+            ref other => panic!("Expected an Xml error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_wanted_list_dir_fail_fast() {
+        let dir_path = mixed_directory("fail_fast");
+        let directory_load = load_wanted_list_dir(&dir_path, &LoadOptions::default());
+        std::fs::remove_dir_all(&dir_path).unwrap();
+        match directory_load {
+            Err(BricklineError::Xml(..)) => (),
+            other => panic!("Expected an Xml error, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_wanted_list_dir_non_utf8_name() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut dir_path = std::env::temp_dir();
+        dir_path.push(format!("brickline_{}_non_utf8", std::process::id()));
+        std::fs::create_dir_all(&dir_path).unwrap();
+        let xml_path = dir_path.join(OsStr::from_bytes(b"list_\xff.xml"));
+        std::fs::copy(
+            common::get_resource_path("test_wanted_list_1.xml"),
+            &xml_path,
+        )
+        .unwrap();
+
+        let directory_load = load_wanted_list_dir(&dir_path, &LoadOptions::default());
+        std::fs::remove_dir_all(&dir_path).unwrap();
+        let directory_load = directory_load.unwrap();
+        assert_eq!(directory_load.loaded.len(), 1);
+        assert_eq!(directory_load.loaded[0].0, xml_path);
+    }
+
+    #[test]
+    fn test_color_name() {
+        assert_eq!(Color(5).name(), Some("Red"));
+        assert_eq!(Color(11).name(), Some("Black"));
+        assert_eq!(Color(9999).name(), None);
+    }
+
+    #[test]
+    fn test_collect_and_extend_wanted_list() {
+        let mut wanted_list: WantedList = vec![
+            filled_item("3001", Some(1), None),
+            filled_item("3622", Some(2), None),
+            filled_item("3623", Some(3), None),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(item_ids(&wanted_list), vec!["3001", "3622", "3623"]);
+
+        wanted_list.extend(vec![filled_item("3000", None, None)]);
+        assert_eq!(item_ids(&wanted_list), vec!["3001", "3622", "3623", "3000"]);
+    }
+
+    fn subtract_wanted_lists() -> (WantedList, WantedList) {
+        let left = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), Some(2)),
+                filled_item("3622", Some(4), None),
+                filled_item("3623", Some(1), None),
+            ],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(3), None),
+                filled_item("3622", Some(4), None),
+                filled_item("3000", Some(5), None),
+            ],
+        };
+        (left, right)
+    }
+
+    #[test]
+    fn test_subtract_qty() {
+        let (left, right) = subtract_wanted_lists();
+        let subtracted = brickline::subtract_inventories(&left, &right, SubtractMode::Qty);
+        assert_eq!(item_ids(&subtracted), vec!["3001", "3623"]);
+        assert_eq!(subtracted.items[0].min_qty, Some(MinQty(7)));
+        assert_eq!(subtracted.items[0].qty_filled, Some(QtyFilled(2)));
+        assert_eq!(subtracted.items[1], left.items[2]);
+    }
+
+    #[test]
+    fn test_subtract_filled() {
+        let (left, right) = subtract_wanted_lists();
+        let subtracted = brickline::subtract_inventories(&left, &right, SubtractMode::Filled);
+        assert_eq!(item_ids(&subtracted), vec!["3001", "3622", "3623"]);
+        assert_eq!(subtracted.items[0].min_qty, Some(MinQty(10)));
+        assert_eq!(subtracted.items[0].qty_filled, Some(QtyFilled(5)));
+        assert_eq!(subtracted.items[1].min_qty, Some(MinQty(4)));
+        assert_eq!(subtracted.items[1].qty_filled, Some(QtyFilled(4)));
+        assert_eq!(subtracted.items[2], left.items[2]);
+    }
+
+    #[test]
+    fn test_subtract_duplicate_entries() {
+        let need = WantedList {
+            items: vec![filled_item("3001", Some(10), None)],
+        };
+        let bought = WantedList {
+            items: vec![
+                filled_item("3001", Some(3), None),
+                filled_item("3001", Some(4), None),
+            ],
+        };
+        let subtracted = brickline::subtract_inventories(&need, &bought, SubtractMode::Qty);
+        assert_eq!(min_qtys(&subtracted), vec![Some(MinQty(3))]);
+        assert_eq!(subtracted, brickline::restock_order(&need, &bought));
+        let filled = brickline::subtract_inventories(&need, &bought, SubtractMode::Filled);
+        assert_eq!(filled.items[0].qty_filled, Some(QtyFilled(7)));
+
+        // Duplicate lefthand entries share the righthand quantity in order
+        let need = WantedList {
+            items: vec![
+                filled_item("3001", Some(4), None),
+                filled_item("3002", Some(1), None),
+                filled_item("3001", Some(10), None),
+            ],
+        };
+        let bought = WantedList {
+            items: vec![filled_item("3001", Some(6), None)],
+        };
+        let subtracted = brickline::subtract_inventories(&need, &bought, SubtractMode::Qty);
+        assert_eq!(item_ids(&subtracted), vec!["3002", "3001"]);
+        assert_eq!(
+            min_qtys(&subtracted),
+            vec![Some(MinQty(1)), Some(MinQty(8))]
+        );
+
+        let filled = brickline::subtract_inventories(&need, &bought, SubtractMode::Filled);
+        let qtys_filled: Vec<Option<QtyFilled>> = filled
+            .items
+            .iter()
+            .map(|item| item.qty_filled.clone())
+            .collect();
+        assert_eq!(
+            qtys_filled,
+            vec![Some(QtyFilled(4)), None, Some(QtyFilled(2))]
+        );
+
+        // Leftovers go to the last lefthand entry
+        let bought = WantedList {
+            items: vec![filled_item("3001", Some(20), None)],
+        };
+        let filled = brickline::subtract_inventories(&need, &bought, SubtractMode::Filled);
+        assert_eq!(filled.items[0].qty_filled, Some(QtyFilled(4)));
+        assert_eq!(filled.items[2].qty_filled, Some(QtyFilled(16)));
+    }
+
+    fn priced_item(item_id: &str, max_price: Option<i64>) -> Item {
+        let mut item = filled_item(item_id, Some(1), None);
+        item.max_price = max_price.map(MaxPrice);
+        item
+    }
+
+    fn priced_wanted_list() -> WantedList {
+        WantedList {
+            items: vec![
+                priced_item("3001", Some(25)),
+                priced_item("3622", Some(26)),
+                priced_item("3623", None),
+                priced_item("3000", Some(-100)),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_filter_by_max_price_excluding_unpriced() {
+        let item_filter = ItemFilter {
+            max_price_lte: Some(MaxPrice(25)),
+            ..ItemFilter::default()
+        };
+        let filtered = priced_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(item_ids(&filtered), vec!["3001"]);
+    }
+
+    #[test]
+    fn test_filter_by_max_price_including_unpriced() {
+        let item_filter = ItemFilter {
+            max_price_lte: Some(MaxPrice(25)),
+            include_unpriced: true,
+            ..ItemFilter::default()
+        };
+        let filtered = priced_wanted_list().filter(|item| item_filter.matches(item));
+        assert_eq!(item_ids(&filtered), vec!["3001", "3623", "3000"]);
+    }
+
+    #[test]
+    fn test_display_item_and_wanted_list() {
+        let mut item = filled_item("3001", Some(100), Some(40));
+        item.max_price = Some(MaxPrice(125));
+        item.condition = Some(Condition::New);
+        assert_eq!(
+            item.to_string(),
+            "P 3001 color=5 qty=100 filled=40 price=1.25 condition=N"
+        );
+
+        let bare = Item::build_test_item(
+            ItemType::Minifig,
+            ItemID(String::from("sw0001")),
+            None,
+            None,
+        );
+        let wanted_list = WantedList {
+            items: vec![item, bare],
+        };
+        assert_eq!(
+            wanted_list.to_string(),
+            "P 3001 color=5 qty=100 filled=40 price=1.25 condition=N\nM sw0001"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_4.xml");
+        let mut shuffled = wanted_list.clone();
+        shuffled.items.reverse();
+        shuffled.items.swap(0, 7);
+        assert_ne!(shuffled, wanted_list);
+        assert_eq!(shuffled.fingerprint(), wanted_list.fingerprint());
+
+        shuffled.items[3].min_qty = Some(MinQty(999));
+        assert_ne!(shuffled.fingerprint(), wanted_list.fingerprint());
+    }
+
+    #[test]
+    fn test_exclude_list() {
+        let exclude_list = "3001\n\n3622 11\n".parse::<ExcludeList>().unwrap();
+        let mut black_3622 = filled_item("3622", None, None);
+        black_3622.color = Some(Color(11));
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", None, None),
+                filled_item("3622", None, None),
+                black_3622,
+            ],
+        };
+        let kept = wanted_list.filter(|item| !exclude_list.excludes(item));
+        assert_eq!(kept.items, vec![wanted_list.items[1].clone()]);
+
+        assert!("3001 red".parse::<ExcludeList>().is_err());
+        assert!("3001 5 extra".parse::<ExcludeList>().is_err());
+    }
+
+    fn join_prices(left: Option<i64>, right: Option<i64>, price: PricePolicy) -> Option<MaxPrice> {
+        let join_options = JoinOptions {
+            price,
+            ..JoinOptions::default()
+        };
+        let joined = brickline::join_inventories_with(
+            &WantedList {
+                items: vec![priced_item("3001", left)],
+            },
+            &WantedList {
+                items: vec![priced_item("3001", right)],
+            },
+            &join_options,
+        );
+        joined.items[0].max_price.clone()
+    }
+
+    #[test]
+    fn test_join_price_policy_min() {
+        assert_eq!(
+            join_prices(Some(25), Some(10), PricePolicy::Min),
+            Some(MaxPrice(10))
+        );
+        assert_eq!(
+            join_prices(None, Some(10), PricePolicy::Min),
+            Some(MaxPrice(10))
+        );
+        assert_eq!(
+            join_prices(Some(-100), Some(10), PricePolicy::Min),
+            Some(MaxPrice(10))
+        );
+    }
+
+    #[test]
+    fn test_join_price_policy_max() {
+        assert_eq!(
+            join_prices(Some(25), Some(10), PricePolicy::Max),
+            Some(MaxPrice(25))
+        );
+        assert_eq!(
+            join_prices(Some(25), None, PricePolicy::Max),
+            Some(MaxPrice(25))
+        );
+        assert_eq!(join_prices(None, None, PricePolicy::Max), None);
+    }
+
+    #[test]
+    fn test_join_price_policy_keep_left() {
+        assert_eq!(join_prices(None, Some(10), PricePolicy::KeepLeft), None);
+    }
+
+    #[test]
+    fn test_item_type_predicates() {
+        let wanted_list = mixed_wanted_list();
+        let parts = wanted_list.filter(Item::is_part);
+        assert_eq!(item_ids(&parts), vec!["3001", "3622"]);
+        let minifigs = wanted_list.filter(Item::is_minifig);
+        assert_eq!(item_ids(&minifigs), vec!["sw0001"]);
+        let sets = wanted_list.filter(Item::is_set);
+        assert_eq!(item_ids(&sets), vec!["6020-1"]);
+
+        let gear = Item::build_test_item(ItemType::Gear, ItemID(String::from("852")), None, None);
+        assert!(gear.is_gear());
+        assert!(!gear.is_part() && !gear.is_minifig() && !gear.is_set());
+    }
+
+    #[test]
+    fn test_write_wanted_list_to_vec() {
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        for (indent, item_line) in [
+            (None, "<INVENTORY><ITEM>"),
+            (Some(Indent::Spaces(2)), "\n  <ITEM>\n"),
+            (Some(Indent::Spaces(4)), "\n    <ITEM>\n"),
+            (Some(Indent::Tab), "\n\t<ITEM>\n"),
+        ] {
+            let mut buffer: Vec<u8> = Vec::new();
+            brickline::write_wanted_list(wanted_list.clone(), indent, &mut buffer).unwrap();
+            let xml_string = String::from_utf8(buffer).unwrap();
+            assert!(xml_string.contains(item_line), "{}", xml_string);
+            let reparsed =
+                WantedList::try_from(brickline::parse_wanted_list(&xml_string).unwrap()).unwrap();
+            assert_eq!(reparsed, wanted_list);
+        }
+    }
+
+    #[test]
+    fn test_join_changes() {
+        let left = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let right = common::resource_name_to_wanted_list("test_wanted_list_2.xml");
+        let (joined, changes) = join_inventories_tracked(&left, &right, &JoinOptions::default());
+        assert_eq!(joined, brickline::join_inventories(&left, &right));
+
+        let item_color = |item_id: &str| OwnedJoinKey {
+            item_id: ItemID::from(item_id),
+            color: Some(Color(11)),
+            condition: None,
+        };
+        assert_eq!(changes.incremented.len(), 2);
+        assert!(changes.incremented.contains(&item_color("3622")));
+        assert!(!changes.incremented.contains(&item_color("3000")));
+        assert_eq!(
+            changes.added.into_iter().collect::<Vec<_>>(),
+            vec![item_color("3000")]
+        );
+    }
+
+    #[test]
+    fn test_join_changes_id_color_condition() {
+        let mut new_3001 = filled_item("3001", Some(2), None);
+        new_3001.condition = Some(Condition::New);
+        let mut used_3001 = filled_item("3001", Some(3), None);
+        used_3001.condition = Some(Condition::Used);
+        let join_options = JoinOptions {
+            key: JoinKey::IdColorCondition,
+            ..JoinOptions::default()
+        };
+        let changed_only = |left: &WantedList, right: &WantedList| {
+            let (joined, changes) = join_inventories_tracked(left, right, &join_options);
+            let changed = joined.filter(|item| {
+                changes
+                    .incremented
+                    .contains(&join_options.owned_key_for(item))
+            });
+            (changed, changes)
+        };
+
+        // Only the New lefthand Item is incremented, not the Used one
+        let left = WantedList {
+            items: vec![new_3001.clone(), used_3001.clone()],
+        };
+        let right = WantedList {
+            items: vec![new_3001.clone()],
+        };
+        let (changed, changes) = changed_only(&left, &right);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed.items[0].condition, Some(Condition::New));
+        assert_eq!(changed.items[0].min_qty, Some(MinQty(4)));
+        assert!(changes.added.is_empty());
+
+        // An added Used Item doesn't hide the incremented New Item
+        let left = WantedList {
+            items: vec![new_3001.clone()],
+        };
+        let right = WantedList {
+            items: vec![used_3001.clone(), new_3001.clone()],
+        };
+        let (changed, changes) = changed_only(&left, &right);
+        assert_eq!(changes.incremented.len(), 1);
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed.items[0].condition, Some(Condition::New));
+        assert_eq!(
+            changes.added.into_iter().next().unwrap().condition,
+            Some(Condition::Used)
+        );
+    }
+
+    #[test]
+    fn test_wanted_list_len_and_iter() {
+        let empty = WantedList { items: vec![] };
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+
+        let wanted_list = mixed_wanted_list();
+        assert_eq!(wanted_list.len(), wanted_list.items.len());
+        assert!(!wanted_list.is_empty());
+        let borrowed_ids: Vec<&ItemID> = (&wanted_list)
+            .into_iter()
+            .map(|item| &item.item_id)
+            .collect();
+        assert_eq!(borrowed_ids.len(), wanted_list.len());
+        let owned: Vec<Item> = wanted_list.clone().into_iter().collect();
+        assert_eq!(owned, wanted_list.items);
+    }
+
+    #[test]
+    fn test_join_nocolor_wildcard() {
+        let mut red_3001 = filled_item("3001", Some(2), None);
+        red_3001.color = Some(Color(5));
+        let mut black_3001 = filled_item("3001", Some(3), None);
+        black_3001.color = Some(Color(11));
+        let mut colorless_3001 = filled_item("3001", Some(10), None);
+        colorless_3001.color = None;
+        let mut colorless_3622 = filled_item("3622", Some(1), None);
+        colorless_3622.color = None;
+        let left = WantedList {
+            items: vec![red_3001, black_3001],
+        };
+        let right = WantedList {
+            items: vec![colorless_3001, colorless_3622],
+        };
+        let join_options = JoinOptions {
+            nocolor_wildcard: true,
+            ..JoinOptions::default()
+        };
+
+        let (joined, changes) = brickline::join_inventories_tracked(&left, &right, &join_options);
+        assert_eq!(item_ids(&joined), vec!["3001", "3001", "3622"]);
+        assert_eq!(
+            min_qtys(&joined),
+            vec![Some(MinQty(12)), Some(MinQty(13)), Some(MinQty(1))]
+        );
+        assert_eq!(changes.incremented.len(), 2);
+        assert_eq!(changes.added.len(), 1);
+
+        let plain = brickline::join_inventories_with(&left, &right, &JoinOptions::default());
+        assert_eq!(plain.len(), 4);
+    }
+
+    #[test]
+    fn test_partition_by_type() {
+        let wanted_list = mixed_wanted_list();
+        let partitions = wanted_list.partition_by_type();
+        assert_eq!(
+            partitions.keys().cloned().collect::<Vec<ItemType>>(),
+            vec![ItemType::Set, ItemType::Part, ItemType::Minifig]
+        );
+        assert_eq!(item_ids(&partitions[&ItemType::Set]), vec!["6020-1"]);
+        assert_eq!(item_ids(&partitions[&ItemType::Part]), vec!["3001", "3622"]);
+        assert_eq!(item_ids(&partitions[&ItemType::Minifig]), vec!["sw0001"]);
+        assert!(!partitions.contains_key(&ItemType::Gear));
+    }
+
+    #[test]
+    fn test_join_then_cap_min_qty() {
+        let left = WantedList {
+            items: vec![
+                filled_item("3001", Some(30), None),
+                filled_item("3622", Some(2), None),
+            ],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(40), None),
+                filled_item("3622", Some(3), None),
+                filled_item("3623", None, None),
+            ],
+        };
+        let mut joined = brickline::join_inventories(&left, &right);
+        joined.cap_min_qty(50);
+        assert_eq!(
+            min_qtys(&joined),
+            vec![Some(MinQty(50)), Some(MinQty(5)), None]
+        );
+    }
+
+    #[test]
+    fn test_wanted_list_get() {
+        let wanted_list = mixed_wanted_list();
+        let item = wanted_list
+            .get(&ItemID(String::from("3001")), Some(&Color(5)))
+            .unwrap();
+        assert_eq!(item.item_type, ItemType::Part);
+        assert!(wanted_list.contains(&ItemID(String::from("3001")), Some(&Color(5))));
+
+        assert_eq!(
+            wanted_list.get(&ItemID(String::from("3001")), Some(&Color(11))),
+            None
+        );
+        assert!(!wanted_list.contains(&ItemID(String::from("3001")), None));
+        assert!(!wanted_list.contains(&ItemID(String::from("9999")), Some(&Color(5))));
+    }
+
+    #[test]
+    fn test_join_saturates_large_min_qty() {
+        let left = WantedList {
+            items: vec![filled_item("3001", Some(i32::MAX - 5), None)],
+        };
+        let right = WantedList {
+            items: vec![filled_item("3001", Some(10), None)],
+        };
+        let joined = brickline::join_inventories(&left, &right);
+        assert_eq!(min_qtys(&joined), vec![Some(MinQty(i32::MAX))]);
+
+        let joined = brickline::join_many(&[left.clone(), left.clone(), left]);
+        assert_eq!(min_qtys(&joined), vec![Some(MinQty(i32::MAX))]);
+    }
+
+    #[test]
+    fn test_join_statistics_saturate_large_min_qty() {
+        let mut left_item = filled_item("3001", Some(i32::MAX), None);
+        left_item.max_price = Some(MaxPrice(i64::MAX / 2));
+        let mut right_item = filled_item("3002", Some(i32::MAX), None);
+        right_item.max_price = Some(MaxPrice(i64::MAX / 2));
+        let joined = brickline::join_inventories(
+            &WantedList {
+                items: vec![left_item.clone(), right_item.clone()],
+            },
+            &WantedList {
+                items: vec![left_item, right_item],
+            },
+        );
+        assert_eq!(
+            min_qtys(&joined),
+            vec![Some(MinQty(i32::MAX)), Some(MinQty(i32::MAX))]
+        );
+
+        let statistics = gen_statistics(&joined);
+        assert_eq!(statistics.total_parts, i32::MAX);
+        assert_eq!(statistics.total_parts_remaining, i32::MAX);
+        assert_eq!(statistics.total_max_price, MaxPrice(i64::MAX));
+    }
+
+    #[test]
+    fn test_restock_order() {
+        let target = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), None),
+                filled_item("3622", Some(4), None),
+                filled_item("3623", Some(6), None),
+            ],
+        };
+        let current = WantedList {
+            items: vec![
+                filled_item("3001", Some(3), None),
+                filled_item("3001", Some(2), None),
+                filled_item("3622", Some(9), None),
+                filled_item("3004", Some(1), None),
+            ],
+        };
+        let order = brickline::restock_order(&target, &current);
+        // Understocked 3001 is topped up, overstocked 3622 is left out, and
+        // 3623 with none in stock is ordered in full
+        assert_eq!(item_ids(&order), vec!["3001", "3623"]);
+        assert_eq!(min_qtys(&order), vec![Some(MinQty(5)), Some(MinQty(6))]);
+    }
+
+    #[test]
+    fn test_item_color_key() {
+        let item = filled_item("3001", Some(10), None);
+        let mut same_lot = filled_item("3001", Some(2), Some(1));
+        same_lot.remarks = None;
+        assert_eq!(item.color_key(), same_lot.color_key());
+
+        let mut other_color = item.clone();
+        other_color.color = Some(Color(11));
+        assert_ne!(item.color_key(), other_color.color_key());
+    }
+
+    #[test]
+    fn test_apply_price_guide() {
+        let guide = "ItemID,Color,AvgPrice\n3001,5,0.12\n\n3622,,1.50\n3623,11,0.40\n"
+            .parse::<PriceGuide>()
+            .unwrap();
+        let mut colorless_3622 = priced_item("3622", Some(99));
+        colorless_3622.color = None;
+        let mut wanted_list = WantedList {
+            items: vec![
+                priced_item("3001", None),
+                colorless_3622,
+                priced_item("3623", Some(25)),
+            ],
+        };
+
+        assert_eq!(brickline::apply_price_guide(&mut wanted_list, &guide), 2);
+        let prices: Vec<Option<MaxPrice>> = wanted_list
+            .iter()
+            .map(|item| item.max_price.clone())
+            .collect();
+        // 3623 is only in the guide in another color, so keeps its price
+        assert_eq!(
+            prices,
+            vec![Some(MaxPrice(12)), Some(MaxPrice(150)), Some(MaxPrice(25))]
+        );
+
+        let marked_up = "3001,5,0.12\n"
+            .parse::<PriceGuide>()
+            .unwrap()
+            .adjusted(Markup(5000), None);
+        brickline::apply_price_guide(&mut wanted_list, &marked_up);
+        assert_eq!(wanted_list.items[0].max_price, Some(MaxPrice(18)));
+
+        assert!("3001,red,0.12".parse::<PriceGuide>().is_err());
+        assert!("3001,5".parse::<PriceGuide>().is_err());
+    }
+
+    #[test]
+    fn test_price_guide_markup_and_round() {
+        let guide = "3001,5,0.12\n3002,5,0.50\n3003,5,0.25\n3004,5,-1.00\n"
+            .parse::<PriceGuide>()
+            .unwrap();
+        let price = |guide: &PriceGuide, id: &str| {
+            guide
+                .price_for(&filled_item(id, None, None))
+                .cloned()
+                .unwrap()
+        };
+
+        let rounded = guide.adjusted("10".parse::<Markup>().unwrap(), Some(&MaxPrice(5)));
+        // 0.132 is nearest 0.15
+        assert_eq!(price(&rounded, "3001"), MaxPrice(15));
+        // 0.55 is already a multiple of 0.05
+        assert_eq!(price(&rounded, "3002"), MaxPrice(55));
+        // Bricklink's "no maximum" isn't marked up
+        assert_eq!(price(&rounded, "3004"), MaxPrice(-100));
+
+        let guide = "3002,5,0.50\n3003,5,0.25\n".parse::<PriceGuide>().unwrap();
+        let halves = guide.adjusted("5".parse::<Markup>().unwrap(), Some(&MaxPrice(5)));
+        // 0.525 is exactly halfway between 0.50 and 0.55, and rounds up
+        assert_eq!(price(&halves, "3002"), MaxPrice(55));
+
+        let guide = "3003,5,0.25\n".parse::<PriceGuide>().unwrap();
+        let cents = guide.adjusted("12.5".parse::<Markup>().unwrap(), None);
+        // 0.28125 to the nearest cent
+        assert_eq!(price(&cents, "3003"), MaxPrice(28));
+
+        assert!("-100".parse::<Markup>().is_err());
+        assert!("ten".parse::<Markup>().is_err());
+    }
+
+    #[test]
+    fn test_wanted_list_retain() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.retain(|item| item.is_part() && item.color == Some(Color(5)));
+        assert_eq!(item_ids(&wanted_list), vec!["3001"]);
+    }
+
+    #[test]
+    fn test_item_validate() {
+        assert_eq!(filled_item("3001", Some(10), Some(4)).validate(), Ok(()));
+        assert_eq!(filled_item("3001", None, Some(4)).validate(), Ok(()));
+
+        let mut bad_color = filled_item("3001", Some(10), None);
+        bad_color.color = Some(Color(9999));
+        assert_eq!(
+            bad_color.validate(),
+            Err(vec![ItemProblem::UnknownColor(9999)])
+        );
+        assert_eq!(
+            filled_item(" ", Some(0), None).validate(),
+            Err(vec![
+                ItemProblem::EmptyItemId,
+                ItemProblem::NonPositiveMinQty(0)
+            ])
+        );
+        assert_eq!(
+            filled_item("3001", Some(2), Some(3)).validate(),
+            Err(vec![ItemProblem::QtyFilledExceedsMinQty {
+                qty_filled: 3,
+                min_qty: 2
+            }])
+        );
+    }
+
+    #[test]
+    fn test_parse_brickstore_wanted_list() {
+        let xml_string = common::resource_name_to_string("test_brickstore_list.bsx");
+        let wanted_list = WantedList::try_from(parse_wanted_list(&xml_string).unwrap()).unwrap();
+
+        let expected = WantedList {
+            items: vec![
+                ItemBuilder::new()
+                    .item_type(ItemType::Part)
+                    .item_id(ItemID(String::from("3001")))
+                    .color(Color(5))
+                    .min_qty(MinQty(12))
+                    .max_price(MaxPrice(15))
+                    .condition(Condition::New)
+                    .remarks(Remarks(String::from("Castle wall")))
+                    .build()
+                    .unwrap(),
+                ItemBuilder::new()
+                    .item_type(ItemType::Minifig)
+                    .item_id(ItemID(String::from("sw0001a")))
+                    .min_qty(MinQty(1))
+                    .build()
+                    .unwrap(),
+            ],
+        };
+        assert_eq!(wanted_list, expected);
+    }
+
+    #[test]
+    fn test_parse_unknown_root() {
+        match parse_wanted_list("<WANTEDLIST><ITEM></ITEM></WANTEDLIST>") {
+            Err(e @ BricklineError::Xml(_, None)) => {
+                assert!(e.to_string().contains("<WANTEDLIST>"));
+            }
+            other => panic!("Expected an unsupported root error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sort_by_field() {
+        let item = |id: &str, color: Option<i32>, min_qty: Option<i32>| {
+            Item::build_test_item(
+                ItemType::Part,
+                ItemID(String::from(id)),
+                color.map(Color),
+                min_qty.map(MinQty),
+            )
+        };
+        let mut wanted_list = WantedList {
+            items: vec![
+                item("3622", Some(11), Some(4)),
+                item("3001", Some(5), Some(10)),
+                item("3039", None, None),
+                item("2456", Some(5), Some(4)),
+            ],
+        };
+
+        wanted_list.sort_by_field(SortKey::Qty, true);
+        // The tied 2456 and 3622 fall back to ItemID order
+        assert_eq!(item_ids(&wanted_list), vec!["3001", "2456", "3622", "3039"]);
+
+        wanted_list.sort_by_field(SortKey::Color, false);
+        assert_eq!(item_ids(&wanted_list), vec!["3039", "2456", "3001", "3622"]);
+    }
+
+    #[test]
+    fn test_join_tag_source() {
+        let left = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), None),
+                filled_item("3622", Some(2), None),
+            ],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(4), None),
+                Item::build_test_item(
+                    ItemType::Part,
+                    ItemID(String::from("3039")),
+                    None,
+                    Some(MinQty(1)),
+                ),
+            ],
+        };
+        let join_options = JoinOptions {
+            source_tag: Some(String::from("list2")),
+            ..JoinOptions::default()
+        };
+        let (joined, _) = join_inventories_tracked(&left, &right, &join_options);
+
+        let remarks = |id: &str| {
+            joined
+                .iter()
+                .find(|item| item.item_id.0 == id)
+                .and_then(|item| item.remarks.clone())
+        };
+        // Incremented items keep their remarks with the tag after them
+        assert_eq!(
+            remarks("3001"),
+            Some(Remarks(String::from("Keep me [from list2]")))
+        );
+        // Right-only items are tagged
+        assert_eq!(remarks("3039"), Some(Remarks(String::from("[from list2]"))));
+        // Untouched lefthand items aren't
+        assert_eq!(remarks("3622"), Some(Remarks(String::from("Keep me"))));
+    }
+
+    #[test]
+    fn test_prune_filled() {
+        let mut wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), Some(10)),
+                filled_item("3002", Some(10), Some(4)),
+                filled_item("3003", Some(10), None),
+                filled_item("3004", None, Some(1)),
+                filled_item("3005", Some(2), Some(5)),
+                filled_item("3006", None, None),
+            ],
+        };
+        wanted_list.prune_filled();
+        assert_eq!(item_ids(&wanted_list), vec!["3002", "3003", "3006"]);
+        // Partially filled Items are kept as they are
+        assert_eq!(wanted_list.items[0].qty_filled, Some(QtyFilled(4)));
+    }
+
+    #[test]
+    fn test_item_type_and_condition_as_map_keys() {
+        let item_types = vec![
+            ItemType::Set,
+            ItemType::Part,
+            ItemType::Minifig,
+            ItemType::Book,
+            ItemType::Gear,
+            ItemType::Catalog,
+            ItemType::Instruction,
+            ItemType::OriginalBox,
+            ItemType::UnsortedLot,
+        ];
+        let by_type: BTreeMap<ItemType, usize> = item_types
+            .iter()
+            .rev()
+            .cloned()
+            .enumerate()
+            .map(|(i, item_type)| (item_type, i))
+            .collect();
+        // Keys come back in declaration order, whatever the insertion order
+        assert_eq!(by_type.keys().cloned().collect::<Vec<_>>(), item_types);
+
+        let conditions = vec![
+            Condition::New,
+            Condition::Used,
+            Condition::Complete,
+            Condition::Incomplete,
+            Condition::Sealed,
+            Condition::NotProvided,
+        ];
+        let by_condition: BTreeMap<Condition, usize> = conditions
+            .iter()
+            .rev()
+            .cloned()
+            .enumerate()
+            .map(|(i, condition)| (condition, i))
+            .collect();
+        assert_eq!(by_condition.keys().cloned().collect::<Vec<_>>(), conditions);
+    }
+
+    #[test]
+    fn test_item_merge_fields() {
+        let mut left = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            Some(MinQty(2)),
+        );
+        left.remarks = Some(Remarks(String::from("Left")));
+        let mut right = filled_item("3001", Some(3), Some(1));
+        right.condition = Some(Condition::Used);
+        right.max_price = Some(MaxPrice(150));
+        right.extra.insert(String::from("NOTE"), String::from("x"));
+
+        left.merge_fields(&right);
+        // None fields are populated from the right
+        assert_eq!(left.condition, Some(Condition::Used));
+        assert_eq!(left.max_price, Some(MaxPrice(150)));
+        assert_eq!(left.extra.get("NOTE"), Some(&String::from("x")));
+        // Fields the left already has are kept, and quantities are untouched
+        assert_eq!(left.remarks, Some(Remarks(String::from("Left"))));
+        assert_eq!(left.min_qty, Some(MinQty(2)));
+        assert_eq!(left.qty_filled, None);
+    }
+
+    #[test]
+    fn test_join_fill_missing() {
+        let left = WantedList {
+            items: vec![Item::build_test_item(
+                ItemType::Part,
+                ItemID(String::from("3001")),
+                Some(Color(5)),
+                Some(MinQty(2)),
+            )],
+        };
+        let mut right_item = filled_item("3001", Some(3), None);
+        right_item.condition = Some(Condition::New);
+        let right = WantedList {
+            items: vec![right_item],
+        };
+
+        let joined = brickline::join_inventories_with(&left, &right, &JoinOptions::default());
+        assert_eq!(joined.items[0].condition, None);
+
+        let join_options = JoinOptions {
+            fill_missing: true,
+            ..JoinOptions::default()
+        };
+        let joined = brickline::join_inventories_with(&left, &right, &join_options);
+        assert_eq!(joined.items[0].condition, Some(Condition::New));
+        assert_eq!(
+            joined.items[0].remarks,
+            Some(Remarks(String::from("Keep me")))
+        );
+        // Quantities still sum
+        assert_eq!(joined.items[0].min_qty, Some(MinQty(5)));
+    }
+
+    #[test]
+    fn test_quantity_delta() {
+        let left = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), None),
+                filled_item("3622", Some(4), None),
+                filled_item("3039", None, None),
+                filled_item("3039", Some(2), None),
+            ],
+        };
+        let right = WantedList {
+            items: vec![
+                filled_item("3001", Some(6), None),
+                filled_item("3622", Some(4), None),
+                filled_item("2456", Some(5), None),
+            ],
+        };
+        let key = |id: &str| OwnedItemColorHashKey {
+            item_id: ItemID(String::from(id)),
+            color: Some(Color(5)),
+        };
+
+        let mut expected = BTreeMap::new();
+        expected.insert(key("3001"), -4);
+        expected.insert(key("3622"), 0);
+        // Only on the left: the repeated key is summed, with None counting as 1
+        expected.insert(key("3039"), -3);
+        // Only on the right
+        expected.insert(key("2456"), 5);
+        assert_eq!(quantity_delta(&left, &right), expected);
+    }
+
+    #[test]
+    fn test_item_id_and_remarks_from_str() {
+        assert_eq!(ItemID::from("3001"), ItemID(String::from("3001")));
+        assert_eq!(Remarks::from("Keep me"), Remarks(String::from("Keep me")));
+
+        let item = ItemBuilder::new()
+            .item_type(ItemType::Part)
+            .item_id("3001")
+            .color(Color(5))
+            .remarks("Keep me")
+            .build()
+            .unwrap();
+        assert_eq!(item, filled_item("3001", None, None));
+    }
+
+    #[test]
+    fn test_colors_used() {
+        let mut wanted_list = mixed_wanted_list();
+        wanted_list.items.push(Item::build_test_item(
+            ItemType::Part,
+            ItemID::from("3039"),
+            Some(Color(1)),
+            None,
+        ));
+        let colors: Vec<Color> = wanted_list.colors_used().into_iter().collect();
+        // The colorless 6020-1 isn't counted, and Color 5 is counted once
+        assert_eq!(colors, vec![Color(1), Color(5), Color(11)]);
+
+        assert!(WantedList { items: vec![] }.colors_used().is_empty());
+    }
+
+    #[test]
+    fn test_check_subtract() {
+        let needed = mixed_wanted_list();
+        let mut owned = WantedList {
+            items: vec![
+                filled_item("3001", Some(4), None),
+                Item::build_test_item(ItemType::Set, ItemID::from("6020-1"), None, None),
+            ],
+        };
+        assert!(brickline::check_subtract(&needed, &owned).is_empty());
+
+        // The right ItemID in the wrong Color is flagged too
+        let wrong_color =
+            Item::build_test_item(ItemType::Part, ItemID::from("3622"), Some(Color(5)), None);
+        owned.items.push(wrong_color.clone());
+        assert_eq!(
+            brickline::check_subtract(&needed, &owned),
+            vec![wrong_color]
+        );
+    }
+
+    #[test]
+    fn test_group_by() {
+        let wanted_list = mixed_wanted_list();
+
+        let by_type = wanted_list.group_by(|item| item.item_type.clone());
+        let group_ids = |items: &Vec<&Item>| -> Vec<String> {
+            items.iter().map(|item| item.item_id.0.clone()).collect()
+        };
+        assert_eq!(
+            by_type.keys().cloned().collect::<Vec<ItemType>>(),
+            vec![ItemType::Set, ItemType::Part, ItemType::Minifig]
+        );
+        assert_eq!(group_ids(&by_type[&ItemType::Part]), vec!["3001", "3622"]);
+
+        // Group on a derived key: whether the ItemID starts with a digit
+        let by_numeric = wanted_list.group_by(|item| {
+            item.item_id
+                .0
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_digit())
+        });
+        assert_eq!(group_ids(&by_numeric[&false]), vec!["sw0001"]);
+        assert_eq!(
+            group_ids(&by_numeric[&true]),
+            vec!["3001", "3622", "6020-1"]
+        );
+    }
+
+    #[test]
+    fn test_wanted_list_append() {
+        let mut wanted_list = WantedList {
+            items: vec![
+                filled_item("3622", Some(2), None),
+                filled_item("3001", Some(10), None),
+                filled_item("3039", None, None),
+            ],
+        };
+        let mut new_item = filled_item("2456", Some(3), None);
+        new_item.remarks = None;
+        let mut matching_item = filled_item("3001", Some(4), None);
+        matching_item.remarks = Some(Remarks::from("Dropped"));
+        wanted_list.append(WantedList {
+            items: vec![new_item, matching_item],
+        });
+
+        assert_eq!(item_ids(&wanted_list), vec!["3622", "3001", "3039", "2456"]);
+        assert_eq!(
+            min_qtys(&wanted_list),
+            vec![Some(MinQty(2)), Some(MinQty(14)), None, Some(MinQty(3))]
+        );
+        // The Item appended into keeps its metadata
+        assert_eq!(wanted_list.items[1].remarks, Some(Remarks::from("Keep me")));
+    }
+
+    #[test]
+    fn test_set_condition() {
+        let mut new_item = filled_item("3001", None, None);
+        new_item.condition = Some(Condition::New);
+        let wanted_list = WantedList {
+            items: vec![new_item, filled_item("3002", None, None)],
+        };
+        let conditions = |wanted_list: &WantedList| -> Vec<Option<Condition>> {
+            wanted_list
+                .items
+                .iter()
+                .map(|item| item.condition.clone())
+                .collect()
+        };
+
+        let mut overwritten = wanted_list.clone();
+        overwritten.set_condition_all(Condition::Used);
+        assert_eq!(
+            conditions(&overwritten),
+            vec![Some(Condition::Used), Some(Condition::Used)]
+        );
+
+        let mut only_missing = wanted_list;
+        only_missing.set_condition_missing(Condition::Used);
+        assert_eq!(
+            conditions(&only_missing),
+            vec![Some(Condition::New), Some(Condition::Used)]
+        );
+    }
+
+    #[test]
+    fn test_map_items_clears_remarks() {
+        let wanted_list = WantedList {
+            items: vec![
+                filled_item("3001", Some(10), None),
+                filled_item("3002", Some(4), Some(1)),
+            ],
+        };
+        let cleared = wanted_list.map_items(|mut item| {
+            item.remarks = None;
+            item
+        });
+
+        assert_eq!(item_ids(&cleared), vec!["3001", "3002"]);
+        assert!(cleared.items.iter().all(|item| item.remarks.is_none()));
+        // Everything else is left alone
+        assert_eq!(cleared.items[1].qty_filled, Some(QtyFilled(1)));
+    }
 }
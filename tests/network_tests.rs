@@ -0,0 +1,55 @@
+#![cfg(feature = "network")]
+
+extern crate brickline;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Serve a single HTTP response on a local port, returning the URL to
+    /// request
+    fn serve_once(status_line: &'static str, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request).unwrap();
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status_line,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{}/wanted_list.xml", address)
+    }
+
+    #[test]
+    fn test_from_url() {
+        let xml_string = common::resource_name_to_string("test_wanted_list_1.xml");
+        let url = serve_once("200 OK", xml_string);
+        let wanted_list = brickline::from_url(&url).unwrap();
+        assert_eq!(
+            wanted_list,
+            common::resource_name_to_wanted_list("test_wanted_list_1.xml")
+        );
+    }
+
+    #[test]
+    fn test_from_url_error_status() {
+        let url = serve_once("404 Not Found", String::new());
+        assert!(matches!(
+            brickline::from_url(&url),
+            Err(brickline::error::BricklineError::Io(_))
+        ));
+    }
+}
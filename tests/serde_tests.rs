@@ -1,14 +1,33 @@
 extern crate brickline;
 
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 
 use brickline::wanted::{
-    Color, Condition, Item, ItemID, ItemType, MaxPrice, MinQty, Notify, QtyFilled, Remarks,
-    WantedList,
+    Color, Condition, Item, ItemID, ItemType, MaxPrice, MinQty, Notify, ParseError, QtyFilled,
+    Remarks, SerdeItem, SerdeWantedList, WantedList, WantedShow,
 };
 
+use brickline::parse_wanted_list_lenient;
+
+use quick_xml::de::from_str;
+
 mod common;
 
+/// A small linear congruential generator, so the garbage fed to the parser
+/// is the same on every run
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -31,6 +50,9 @@ mod tests {
             notify: None,
             wanted_show: None,
             wanted_list_id: None,
+            alternate: None,
+            counterpart: None,
+            extra: BTreeMap::new(),
         };
         let item_2 = Item {
             item_type: ItemType::Part,
@@ -44,12 +66,15 @@ mod tests {
             notify: None,
             wanted_show: None,
             wanted_list_id: None,
+            alternate: None,
+            counterpart: None,
+            extra: BTreeMap::new(),
         };
         let item_3 = Item {
             item_type: ItemType::Part,
             item_id: ItemID(String::from("3001")),
             color: Some(Color(5)),
-            max_price: Some(MaxPrice(1.00)),
+            max_price: Some(MaxPrice(100)),
             min_qty: Some(MinQty(100)),
             qty_filled: None,
             condition: Some(Condition::New),
@@ -57,9 +82,12 @@ mod tests {
             notify: Some(Notify::N),
             wanted_show: None,
             wanted_list_id: None,
+            alternate: None,
+            counterpart: None,
+            extra: BTreeMap::new(),
         };
         let items = vec![item_1, item_2, item_3];
-        let expected_wanted_list = WantedList { items: items };
+        let expected_wanted_list = WantedList { items };
         assert_eq!(bricklink_wanted_list, expected_wanted_list);
     }
 
@@ -72,7 +100,7 @@ mod tests {
             Some(MinQty(4)),
         );
         let items = vec![item_1];
-        let wanted_list = WantedList { items: items };
+        let wanted_list = WantedList { items };
         let stringified = String::try_from(wanted_list).unwrap();
         let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
                 <INVENTORY>\
@@ -109,7 +137,7 @@ mod tests {
             Some(MinQty(4)),
         );
         let items = vec![item_1, item_2, item_3];
-        let wanted_list = WantedList { items: items };
+        let wanted_list = WantedList { items };
         let stringified = String::try_from(wanted_list).unwrap();
         let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
                 <INVENTORY>\
@@ -139,7 +167,7 @@ mod tests {
 
     #[test]
     fn test_roundtrips() {
-        for resource_name in vec![
+        for resource_name in [
             "bricklink_example.xml",
             "test_wanted_list_1.xml",
             "test_wanted_list_2.xml",
@@ -153,4 +181,479 @@ mod tests {
             assert_eq!(expected_string, stringified);
         }
     }
+
+    #[test]
+    fn test_json_roundtrips() {
+        for resource_name in [
+            "bricklink_example.xml",
+            "test_wanted_list_1.xml",
+            "test_wanted_list_2.xml",
+            "test_wanted_list_3.xml",
+        ]
+        .iter()
+        {
+            // XML -> WantedList -> JSON -> WantedList -> XML, so the
+            // conversions to and from SerdeItem are both exercised
+            let wanted_list = common::resource_name_to_wanted_list(resource_name);
+            let json =
+                serde_json::to_string(&SerdeWantedList::from(wanted_list.clone()).items).unwrap();
+            let items = serde_json::from_str::<Vec<SerdeItem>>(&json).unwrap();
+            let reparsed = WantedList::try_from(SerdeWantedList { items }).unwrap();
+            assert_eq!(reparsed, wanted_list);
+            let stringified = String::try_from(reparsed).unwrap();
+            let expected_string = common::resource_name_to_string(resource_name);
+            assert_eq!(expected_string, stringified);
+        }
+    }
+
+    #[test]
+    fn test_json_roundtrip_keeps_extra_elements() {
+        let mut serde_item = SerdeItem {
+            item_type: String::from("P"),
+            item_id: String::from("3001"),
+            ..SerdeItem::default()
+        };
+        serde_item
+            .extra
+            .insert(String::from("STOCKROOM"), String::from("B"));
+        let json = serde_json::to_string(&serde_item).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SerdeItem>(&json).unwrap(),
+            serde_item
+        );
+    }
+
+    #[test]
+    fn test_item_json_roundtrip() {
+        let mut item = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3001")),
+            Some(Color(5)),
+            Some(MinQty(12)),
+        );
+        item.max_price = Some(MaxPrice(125));
+        item.qty_filled = Some(QtyFilled(3));
+        item.condition = Some(Condition::Used);
+        item.remarks = Some(Remarks(String::from("for MOC AB154A")));
+        item.notify = Some(Notify::N);
+
+        let json = item.to_json().unwrap();
+        assert!(json.contains("\"min_qty\":12"));
+        assert!(!json.contains("wanted_show"));
+        assert_eq!(Item::from_json(&json).unwrap(), item);
+    }
+
+    #[test]
+    fn test_max_price_cents() {
+        for (price_str, cents) in vec![
+            ("0.01", 1),
+            ("1.00", 100),
+            ("1234.99", 123499),
+            ("-1.00", -100),
+            ("5", 500),
+            ("0.5", 50),
+        ]
+        .into_iter()
+        {
+            let max_price = MaxPrice::try_from(String::from(price_str)).unwrap();
+            assert_eq!(max_price, MaxPrice(cents));
+        }
+
+        assert_eq!(String::from(MaxPrice(1)), "0.01");
+        assert_eq!(String::from(MaxPrice(123499)), "1234.99");
+        assert_eq!(String::from(MaxPrice(-100)), "-1.00");
+        assert_eq!(String::from(MaxPrice(-5)), "-0.05");
+    }
+
+    #[test]
+    fn test_max_price_rounds_extra_decimals() {
+        // Anything past two decimals rounds half away from zero
+        assert_eq!(
+            MaxPrice::try_from(String::from("1.005")).unwrap(),
+            MaxPrice(101)
+        );
+        assert_eq!(
+            MaxPrice::try_from(String::from("1.0049")).unwrap(),
+            MaxPrice(100)
+        );
+        assert_eq!(
+            MaxPrice::try_from(String::from("0.999")).unwrap(),
+            MaxPrice(100)
+        );
+        assert_eq!(
+            MaxPrice::try_from(String::from("-1.005")).unwrap(),
+            MaxPrice(-101)
+        );
+        assert_eq!(
+            String::from(MaxPrice::try_from(String::from("0.125")).unwrap()),
+            "0.13"
+        );
+    }
+
+    #[test]
+    fn test_max_price_comma_and_scientific_notation() {
+        assert_eq!(
+            MaxPrice::try_from(String::from("1,00")).unwrap(),
+            MaxPrice(100)
+        );
+        assert_eq!(
+            MaxPrice::try_from(String::from("-0,5")).unwrap(),
+            MaxPrice(-50)
+        );
+        assert_eq!(
+            MaxPrice::try_from(String::from("2,5")).unwrap(),
+            MaxPrice(250)
+        );
+        // Any other comma could be a thousands separator, which we don't guess at
+        assert_eq!(
+            MaxPrice::try_from(String::from("1,000")),
+            Err(ParseError {
+                field: "MAXPRICE",
+                value: String::from("1,000"),
+            })
+        );
+        assert!(MaxPrice::try_from(String::from("1,000.00")).is_err());
+        assert!(MaxPrice::try_from(String::from("1,")).is_err());
+        assert!(MaxPrice::try_from(String::from("1,0,0")).is_err());
+        assert_eq!(
+            MaxPrice::try_from(String::from("1e2")),
+            Err(ParseError {
+                field: "MAXPRICE",
+                value: String::from("1e2"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_unparseable_max_price() {
+        assert_eq!(
+            MaxPrice::try_from(String::from("abc")),
+            Err(ParseError {
+                field: "MAXPRICE",
+                value: String::from("abc"),
+            })
+        );
+
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <INVENTORY>\
+            <ITEM>\
+            <ITEMTYPE>P</ITEMTYPE>\
+            <ITEMID>3001</ITEMID>\
+            <MAXPRICE>abc</MAXPRICE>\
+            </ITEM>\
+            </INVENTORY>";
+        let serde_wanted_list = from_str::<SerdeWantedList>(xml).unwrap();
+        let err = WantedList::try_from(serde_wanted_list).unwrap_err();
+        assert_eq!(err.to_string(), "Could not parse MAXPRICE value \"abc\"");
+    }
+
+    #[test]
+    fn test_lenient_codes() {
+        for code in ["p", " P ", "\tp\n"].iter() {
+            assert_eq!(ItemType::try_from(String::from(*code)), Ok(ItemType::Part));
+        }
+        for code in ["u", " U ", " u"].iter() {
+            assert_eq!(
+                Condition::try_from(String::from(*code)),
+                Ok(Condition::Used)
+            );
+        }
+        for code in ["y", " Y ", "y "].iter() {
+            assert_eq!(Notify::try_from(String::from(*code)), Ok(Notify::Y));
+        }
+        for code in ["n", " N ", " n"].iter() {
+            assert_eq!(WantedShow::try_from(String::from(*code)), Ok(WantedShow::N));
+        }
+    }
+
+    #[test]
+    fn test_lenient_codes_serialize_canonically() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <INVENTORY>\
+            <ITEM>\
+            <ITEMTYPE> p </ITEMTYPE>\
+            <ITEMID>3001</ITEMID>\
+            <CONDITION>n</CONDITION>\
+            </ITEM>\
+            </INVENTORY>";
+        let serde_wanted_list = from_str::<SerdeWantedList>(xml).unwrap();
+        let wanted_list = WantedList::try_from(serde_wanted_list).unwrap();
+        assert_eq!(wanted_list.items[0].item_type, ItemType::Part);
+        assert_eq!(wanted_list.items[0].condition, Some(Condition::New));
+
+        let stringified = String::try_from(wanted_list).unwrap();
+        assert!(stringified.contains("<ITEMTYPE>P</ITEMTYPE>"));
+        assert!(stringified.contains("<CONDITION>N</CONDITION>"));
+    }
+
+    #[test]
+    fn test_wanted_list_to_string_non_ascii_remarks() {
+        let mut item_1 = Item::build_test_item(
+            ItemType::Part,
+            ItemID(String::from("3622")),
+            Some(Color(11)),
+            Some(MinQty(4)),
+        );
+        item_1.remarks = Some(Remarks(String::from("café 🧱")));
+        let wanted_list = WantedList {
+            items: vec![item_1],
+        };
+        let stringified = String::try_from(wanted_list).unwrap();
+        let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                <INVENTORY>\
+                <ITEM>\
+                <ITEMTYPE>P</ITEMTYPE>\
+                <ITEMID>3622</ITEMID>\
+                <COLOR>11</COLOR>\
+                <MINQTY>4</MINQTY>\
+                <REMARKS>café 🧱</REMARKS>\
+                </ITEM>\
+            </INVENTORY>\
+            ";
+        assert_eq!(String::from(expected), stringified);
+
+        let serde_wanted_list = from_str::<SerdeWantedList>(&stringified).unwrap();
+        let roundtripped = WantedList::try_from(serde_wanted_list).unwrap();
+        assert_eq!(
+            roundtripped.items[0].remarks,
+            Some(Remarks(String::from("café 🧱")))
+        );
+    }
+
+    #[test]
+    fn test_wrapped_wanted_list_loads() {
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_wrapped.xml");
+        let item_ids: Vec<&str> = wanted_list
+            .items
+            .iter()
+            .map(|item| item.item_id.0.as_str())
+            .collect();
+        assert_eq!(item_ids, vec!["3622", "3001"]);
+        assert_eq!(wanted_list.items[1].min_qty, Some(MinQty(100)));
+    }
+
+    #[test]
+    fn test_empty_wanted_list_loads() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><INVENTORY></INVENTORY>";
+        let serde_wanted_list = from_str::<SerdeWantedList>(xml).unwrap();
+        assert!(serde_wanted_list.items.is_empty());
+    }
+
+    #[test]
+    fn test_non_positive_min_qty() {
+        for min_qty in ["0", "-5"].iter() {
+            let xml = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+                <INVENTORY>\
+                <ITEM>\
+                <ITEMTYPE>P</ITEMTYPE>\
+                <ITEMID>3001</ITEMID>\
+                <MINQTY>{}</MINQTY>\
+                </ITEM>\
+                </INVENTORY>",
+                min_qty
+            );
+            let serde_wanted_list = from_str::<SerdeWantedList>(&xml).unwrap();
+            assert_eq!(
+                WantedList::try_from(serde_wanted_list),
+                Err(ParseError {
+                    field: "MINQTY",
+                    value: String::from(*min_qty),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_pretty_xml() {
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        let pretty = wanted_list.clone().to_xml(true).unwrap();
+        assert!(pretty.contains("<INVENTORY>\n  <ITEM>\n    <ITEMTYPE>P</ITEMTYPE>\n"));
+        assert!(pretty.ends_with("  </ITEM>\n</INVENTORY>\n"));
+
+        let serde_wanted_list = from_str::<SerdeWantedList>(&pretty).unwrap();
+        assert_eq!(
+            WantedList::try_from(serde_wanted_list).unwrap(),
+            wanted_list
+        );
+
+        // Compact stays the default
+        assert_eq!(
+            wanted_list.clone().to_xml(false).unwrap(),
+            String::try_from(wanted_list).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_alternate_counterpart_roundtrip() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <INVENTORY>\
+            <ITEM>\
+            <ITEMTYPE>P</ITEMTYPE>\
+            <ITEMID>3001</ITEMID>\
+            <MINQTY>4</MINQTY>\
+            <ALTERNATE>Y</ALTERNATE>\
+            <COUNTERPART>N</COUNTERPART>\
+            </ITEM>\
+            </INVENTORY>";
+        let serde_wanted_list = from_str::<SerdeWantedList>(xml).unwrap();
+        let wanted_list = WantedList::try_from(serde_wanted_list).unwrap();
+        assert_eq!(wanted_list.items[0].alternate, Some(Notify::Y));
+        assert_eq!(wanted_list.items[0].counterpart, Some(Notify::N));
+
+        let stringified = String::try_from(wanted_list).unwrap();
+        assert_eq!(stringified, xml);
+    }
+
+    #[test]
+    fn test_bom_prefixed_file() {
+        let bom_wanted_list = common::resource_name_to_wanted_list("test_wanted_list_1_bom.xml");
+        let wanted_list = common::resource_name_to_wanted_list("test_wanted_list_1.xml");
+        assert_eq!(bom_wanted_list, wanted_list);
+    }
+
+    #[test]
+    fn test_unknown_item_elements_roundtrip() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <INVENTORY>\
+            <ITEM>\
+            <ITEMTYPE>P</ITEMTYPE>\
+            <ITEMID>3001</ITEMID>\
+            <MINQTY>4</MINQTY>\
+            <SOMENEWFIELD>Something &amp; more</SOMENEWFIELD>\
+            </ITEM>\
+            </INVENTORY>";
+        let wanted_list = match brickline::parse_wanted_list(xml) {
+            Ok(serde_wanted_list) => WantedList::try_from(serde_wanted_list).unwrap(),
+            Err(e) => panic!("Failed to parse: {}", e),
+        };
+        assert_eq!(
+            wanted_list.items[0].extra.get("SOMENEWFIELD"),
+            Some(&String::from("Something & more"))
+        );
+
+        let stringified = String::try_from(wanted_list).unwrap();
+        assert_eq!(stringified, xml);
+    }
+
+    #[test]
+    fn test_nested_unknown_item_elements_skipped() {
+        let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+            <INVENTORY>\
+            <ITEM>\
+            <ITEMTYPE>P</ITEMTYPE>\
+            <EXTRA><A>1</A><B><C>2</C></B></EXTRA>\
+            <ITEMID>3001</ITEMID>\
+            <SOMENEWFIELD>kept</SOMENEWFIELD>\
+            <EMPTYFIELD></EMPTYFIELD>\
+            <MINQTY>4</MINQTY>\
+            </ITEM>\
+            <ITEM>\
+            <ITEMTYPE>P</ITEMTYPE>\
+            <ITEMID>3622</ITEMID>\
+            </ITEM>\
+            </INVENTORY>";
+        let wanted_list = match brickline::parse_wanted_list(xml) {
+            Ok(serde_wanted_list) => WantedList::try_from(serde_wanted_list).unwrap(),
+            Err(e) => panic!("Failed to parse: {}", e),
+        };
+        assert_eq!(wanted_list.items.len(), 2);
+        assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(4)));
+        let extra_keys: Vec<&String> = wanted_list.items[0].extra.keys().collect();
+        assert_eq!(extra_keys, vec!["EMPTYFIELD", "SOMENEWFIELD"]);
+        assert_eq!(
+            wanted_list.items[0].extra.get("SOMENEWFIELD"),
+            Some(&String::from("kept"))
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_never_panics() {
+        // Bad codes that used to panic in the From<String> conversions
+        let bad_fields = [
+            "<ITEMTYPE>Z</ITEMTYPE><ITEMID>3001</ITEMID>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><CONDITION>Q</CONDITION>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><NOTIFY>maybe</NOTIFY>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><WANTEDSHOW>?</WANTEDSHOW>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><ALTERNATE>1</ALTERNATE>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><MINQTY>lots</MINQTY>",
+            "<ITEMTYPE>P</ITEMTYPE><ITEMID>3001</ITEMID><COLOR>red</COLOR>",
+        ];
+        for fields in bad_fields.iter() {
+            let xml = format!("<INVENTORY><ITEM>{}</ITEM></INVENTORY>", fields);
+            assert!(parse_wanted_list_lenient(&xml).is_err(), "{}", xml);
+        }
+
+        // Shuffled fragments of a wanted list, which are almost never valid
+        let fragments = [
+            "<INVENTORY>",
+            "</INVENTORY>",
+            "<ITEM>",
+            "</ITEM>",
+            "<ITEMTYPE>",
+            "</ITEMTYPE>",
+            "<CONDITION>",
+            "</CONDITION>",
+            "<BrickStoreXML>",
+            "<Item>",
+            "<?xml version=\"1.0\"?>",
+            "<!--",
+            "-->",
+            "<![CDATA[",
+            "&amp;",
+            "&bogus;",
+            "P",
+            "Z",
+            "-1",
+            "\u{feff}",
+            "é",
+            "<",
+            ">",
+            "/",
+            "\"",
+        ];
+        let mut rng = Lcg(7);
+        for _ in 0..2000 {
+            let len = rng.next(12);
+            let xml: String = (0..len)
+                .map(|_| fragments[rng.next(fragments.len())])
+                .collect();
+            // Only checking that it returns rather than panics
+            let _ = parse_wanted_list_lenient(&xml);
+        }
+
+        // Random text with no elements at all is always an error
+        let alphabet: Vec<char> = "abcXYZ019 \n\t&;\"'é/".chars().collect();
+        for _ in 0..500 {
+            let len = rng.next(40);
+            let garbage: String = (0..len)
+                .map(|_| alphabet[rng.next(alphabet.len())])
+                .collect();
+            assert!(
+                parse_wanted_list_lenient(&garbage).is_err(),
+                "{:?}",
+                garbage
+            );
+        }
+    }
+
+    #[test]
+    fn test_commented_wanted_list() {
+        let xml_string = common::resource_name_to_string("test_commented_wanted_list.xml");
+        let wanted_list = parse_wanted_list_lenient(&xml_string).unwrap();
+
+        // The commented out 9999 isn't read, and the comment inside the
+        // MINQTY doesn't change its value
+        let item_ids: Vec<&str> = wanted_list
+            .iter()
+            .map(|item| item.item_id.0.as_str())
+            .collect();
+        assert_eq!(item_ids, vec!["3001", "3622"]);
+        assert_eq!(wanted_list.items[0].min_qty, Some(MinQty(4)));
+
+        let brickstore = "<BrickStoreXML><!-- export --><Inventory><Item>\
+                          <ItemID>3001</ItemID><!-- red --><ItemTypeID>P</ItemTypeID>\
+                          </Item></Inventory></BrickStoreXML>";
+        assert_eq!(parse_wanted_list_lenient(brickstore).unwrap().len(), 1);
+    }
 }